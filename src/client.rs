@@ -1,6 +1,13 @@
+use crate::clock;
 use crate::exports::heyreach::client::api::*;
-use crate::http::{make_request, make_request_empty, HttpMethod};
+use crate::heyreach::client::webhook_events::{
+    WebhookCampaign, WebhookLead, WebhookMessage, WebhookSender,
+};
+use crate::http::{api_error, HttpClient, HttpMethod};
 use crate::models::*;
+use crate::retry::RetryMode;
+use std::collections::HashSet;
+use std::hash::Hash;
 
 // -------- Helper functions for conversion --------
 
@@ -15,7 +22,7 @@ fn map_campaign_status(status: &str) -> CampaignStatus {
     }
 }
 
-fn campaign_status_to_string(status: &CampaignStatus) -> String {
+pub fn campaign_status_to_string(status: &CampaignStatus) -> String {
     match status {
         CampaignStatus::Draft => "draft",
         CampaignStatus::Active => "active",
@@ -27,37 +34,79 @@ fn campaign_status_to_string(status: &CampaignStatus) -> String {
     .to_string()
 }
 
+fn map_lead_campaign_status(status: &str) -> LeadCampaignStatus {
+    match status.to_lowercase().replace(['_', '-'], "").as_str() {
+        "pending" => LeadCampaignStatus::Pending,
+        "inprogress" => LeadCampaignStatus::InProgress,
+        "finished" => LeadCampaignStatus::Finished,
+        "failed" => LeadCampaignStatus::Failed,
+        "excluded" => LeadCampaignStatus::Excluded,
+        "manuallystopped" => LeadCampaignStatus::ManuallyStopped,
+        _ => LeadCampaignStatus::Unknown,
+    }
+}
+
+fn map_message_sender(sender: &str) -> MessageSender {
+    match sender.to_lowercase().as_str() {
+        "me" | "account" => MessageSender::Account,
+        "correspondent" | "lead" => MessageSender::Lead,
+        _ => MessageSender::Unknown,
+    }
+}
+
 fn map_list_type(list_type: &str) -> ListType {
     match list_type.to_lowercase().as_str() {
-        "leads" => ListType::Leads,
-        "companies" => ListType::Companies,
+        "leads" | "user_list" => ListType::Leads,
+        "companies" | "company_list" => ListType::Companies,
         _ => ListType::Unknown,
     }
 }
 
+fn list_type_to_string(list_type: &ListType) -> Option<String> {
+    match list_type {
+        ListType::Leads => Some("USER_LIST".to_string()),
+        ListType::Companies => Some("COMPANY_LIST".to_string()),
+        ListType::Unknown => None,
+    }
+}
+
 fn map_webhook_event_type(event_type: &str) -> WebhookEventType {
-    match event_type.to_lowercase().as_str() {
-        "connectionrequestsent" | "connection_request_sent" | "connection-request-sent" => {
-            WebhookEventType::ConnectionRequestSent
-        }
-        "connectionaccepted" | "connection_accepted" | "connection-accepted" => {
-            WebhookEventType::ConnectionAccepted
-        }
-        "messagesent" | "message_sent" | "message-sent" => WebhookEventType::MessageSent,
-        "messagereplied" | "message_replied" | "message-replied" => {
-            WebhookEventType::MessageReplied
+    match event_type.to_lowercase().replace(['_', '-'], "").as_str() {
+        "connectionrequestsent" => WebhookEventType::ConnectionRequestSent,
+        "connectionaccepted" | "connectionrequestaccepted" => WebhookEventType::ConnectionAccepted,
+        "messagesent" => WebhookEventType::MessageSent,
+        "messagereplied" | "messagereplyreceived" => WebhookEventType::MessageReplied,
+        "everymessagereplied" | "everymessagereplyreceived" => {
+            WebhookEventType::EveryMessageReplied
         }
-        _ => WebhookEventType::Unknown,
+        "inmailsent" => WebhookEventType::InmailSent,
+        "inmailreplied" | "inmailreplyreceived" => WebhookEventType::InmailReplied,
+        "followsent" => WebhookEventType::FollowSent,
+        "postliked" | "likedpost" => WebhookEventType::PostLiked,
+        "profileviewed" | "viewedprofile" => WebhookEventType::ProfileViewed,
+        "campaigncompleted" => WebhookEventType::CampaignCompleted,
+        "leadtagupdated" => WebhookEventType::LeadTagUpdated,
+        _ => WebhookEventType::Other(event_type.to_string()),
     }
 }
 
+/// HeyReach's `eventType` values, as listed for `CreateWebhook` in its public
+/// API reference.
 fn webhook_event_type_to_string(event_type: &WebhookEventType) -> String {
     match event_type {
-        WebhookEventType::ConnectionRequestSent => "ConnectionRequestSent",
-        WebhookEventType::ConnectionAccepted => "ConnectionAccepted",
-        WebhookEventType::MessageSent => "MessageSent",
-        WebhookEventType::MessageReplied => "MessageReplied",
-        WebhookEventType::Unknown => "Unknown",
+        WebhookEventType::ConnectionRequestSent => "CONNECTION_REQUEST_SENT",
+        WebhookEventType::ConnectionAccepted => "CONNECTION_REQUEST_ACCEPTED",
+        WebhookEventType::MessageSent => "MESSAGE_SENT",
+        WebhookEventType::MessageReplied => "MESSAGE_REPLY_RECEIVED",
+        WebhookEventType::EveryMessageReplied => "EVERY_MESSAGE_REPLY_RECEIVED",
+        WebhookEventType::InmailSent => "INMAIL_SENT",
+        WebhookEventType::InmailReplied => "INMAIL_REPLY_RECEIVED",
+        WebhookEventType::FollowSent => "FOLLOW_SENT",
+        WebhookEventType::PostLiked => "LIKED_POST",
+        WebhookEventType::ProfileViewed => "VIEWED_PROFILE",
+        WebhookEventType::CampaignCompleted => "CAMPAIGN_COMPLETED",
+        WebhookEventType::LeadTagUpdated => "LEAD_TAG_UPDATED",
+        WebhookEventType::Other(raw) => raw.as_str(),
     }
     .to_string()
 }
@@ -98,6 +147,31 @@ fn convert_campaign_summary(dto: CampaignSummaryDto) -> CampaignSummary {
     }
 }
 
+fn convert_list_summary(dto: ListSummaryDto) -> ListSummary {
+    ListSummary {
+        id: dto.id,
+        name: dto.name,
+        total_items_count: dto.total_items_count,
+        list_type: map_list_type(&dto.list_type),
+        creation_time: dto.creation_time,
+        campaign_ids: dto.campaign_ids,
+    }
+}
+
+fn convert_li_account_summary(dto: LiAccountSummaryDto) -> LiAccountSummary {
+    LiAccountSummary {
+        id: dto.id,
+        email_address: dto.email_address,
+        first_name: dto.first_name,
+        last_name: dto.last_name,
+        is_active: dto.is_active,
+        active_campaigns: dto.active_campaigns,
+        auth_is_valid: dto.auth_is_valid,
+        is_valid_navigator: dto.is_valid_navigator,
+        is_valid_recruiter: dto.is_valid_recruiter,
+    }
+}
+
 fn convert_lead_dto(dto: LeadDto) -> Lead {
     Lead {
         first_name: dto.first_name,
@@ -142,20 +216,153 @@ fn convert_lead(lead: Lead) -> LeadDto {
     }
 }
 
+/// Percent-encodes `value` for use as one URL path segment.
+fn encode_path_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+// -------- Pagination --------
+
+/// Largest `limit` the HeyReach API accepts for paged endpoints.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Fetches `MAX_PAGE_SIZE` pages starting at `offset` until `total-count` is
+/// reached, an empty page comes back or `max_items` items have been collected.
+/// A short page is not the end: the API may return fewer items than asked
+/// for while more remain.
+///
+/// `fetch(offset, limit)` returns one page as `(total_count, items)`. Items
+/// are deduplicated by `key`, since inserts during iteration shift later
+/// pages and would otherwise repeat items.
+fn collect_pages<T, K: Eq + Hash>(
+    mut offset: u32,
+    max_items: Option<u32>,
+    key: impl Fn(&T) -> K,
+    mut fetch: impl FnMut(u32, u32) -> Result<(u32, Vec<T>), ApiError>,
+) -> Result<Vec<T>, ApiError> {
+    let max_items = max_items.unwrap_or(u32::MAX) as usize;
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+
+    while items.len() < max_items {
+        let limit = MAX_PAGE_SIZE.min((max_items - items.len()).min(u32::MAX as usize) as u32);
+        let (total_count, page) = fetch(offset, limit)?;
+        let page_len = page.len() as u32;
+
+        for item in page {
+            if seen.insert(key(&item)) {
+                items.push(item);
+            }
+        }
+
+        offset = offset.saturating_add(page_len);
+        if page_len == 0 || offset >= total_count {
+            break;
+        }
+    }
+
+    items.truncate(max_items);
+    Ok(items)
+}
+
+// -------- Chunking --------
+
+/// Most leads HeyReach accepts in a single add-leads request.
+const MAX_LEADS_PER_REQUEST: usize = 100;
+
+/// Sends `items` in chunks of `MAX_LEADS_PER_REQUEST`, one after another, and
+/// sums the counts of the chunks that succeeded.
+///
+/// A failed chunk does not stop the rest: it is reported in `chunks` and its
+/// leads are counted as failed. Only when no chunk succeeds is the first
+/// error returned, so single-chunk calls fail exactly as before.
+fn add_leads_in_chunks<T>(
+    items: Vec<T>,
+    mut send: impl FnMut(Vec<T>) -> Result<CampaignAddLeadsV2ResultDto, ApiError>,
+) -> Result<CampaignAddLeadsV2Result, ApiError> {
+    let mut result = CampaignAddLeadsV2Result {
+        added_leads_count: 0,
+        updated_leads_count: 0,
+        failed_leads_count: 0,
+        chunks: Vec::new(),
+    };
+    let mut first_error = None;
+    let mut any_succeeded = false;
+
+    let mut items = items.into_iter();
+    let mut start = 0u32;
+    loop {
+        let chunk: Vec<T> = items.by_ref().take(MAX_LEADS_PER_REQUEST).collect();
+        if chunk.is_empty() {
+            break;
+        }
+        let lead_count = chunk.len() as u32;
+
+        let outcome = match send(chunk) {
+            Ok(dto) => {
+                any_succeeded = true;
+                result.added_leads_count += dto.added_leads_count;
+                result.updated_leads_count += dto.updated_leads_count;
+                result.failed_leads_count += dto.failed_leads_count;
+                AddLeadsChunk {
+                    start,
+                    lead_count,
+                    added_leads_count: dto.added_leads_count,
+                    updated_leads_count: dto.updated_leads_count,
+                    failed_leads_count: dto.failed_leads_count,
+                    error: None,
+                }
+            }
+            Err(error) => {
+                result.failed_leads_count += lead_count;
+                first_error.get_or_insert_with(|| error.clone());
+                AddLeadsChunk {
+                    start,
+                    lead_count,
+                    added_leads_count: 0,
+                    updated_leads_count: 0,
+                    failed_leads_count: lead_count,
+                    error: Some(error),
+                }
+            }
+        };
+
+        result.chunks.push(outcome);
+        start += lead_count;
+    }
+
+    match first_error {
+        Some(error) if !any_succeeded => Err(error),
+        _ => Ok(result),
+    }
+}
+
 // -------- Auth --------
 
-pub fn check_api_key(api_key: &str) -> Result<(), ApiError> {
-    make_request_empty(
+pub fn check_api_key(http: &HttpClient) -> Result<(), ApiError> {
+    http.make_request_empty(
         HttpMethod::Get,
         "/api/public/auth/CheckApiKey",
-        api_key,
+        RetryMode::Read,
         None::<&()>,
     )
 }
 
 // -------- Campaigns --------
 
-pub fn campaigns_get_all(api_key: &str, filter: CampaignFilter) -> Result<CampaignPage, ApiError> {
+pub fn campaigns_get_all(
+    http: &HttpClient,
+    filter: CampaignFilter,
+) -> Result<CampaignPage, ApiError> {
     let filter_dto = CampaignFilterDto {
         offset: filter.offset,
         limit: filter.limit,
@@ -168,10 +375,10 @@ pub fn campaigns_get_all(api_key: &str, filter: CampaignFilter) -> Result<Campai
         account_ids: filter.account_ids,
     };
 
-    let response: CampaignPageDto = make_request(
+    let response: CampaignPageDto = http.make_request(
         HttpMethod::Post,
         "/api/public/campaign/GetAll",
-        api_key,
+        RetryMode::Read,
         Some(&filter_dto),
     )?;
 
@@ -186,37 +393,65 @@ pub fn campaigns_get_all(api_key: &str, filter: CampaignFilter) -> Result<Campai
     })
 }
 
-pub fn campaigns_get_by_id(api_key: &str, campaign_id: u64) -> Result<CampaignSummary, ApiError> {
-    let response: CampaignSummaryDto = make_request(
+/// Every campaign matching `filter`, from `filter.offset` on; `filter.limit`
+/// is ignored.
+pub fn campaigns_get_all_pages(
+    http: &HttpClient,
+    filter: CampaignFilter,
+    max_items: Option<u32>,
+) -> Result<Vec<CampaignSummary>, ApiError> {
+    collect_pages(
+        filter.offset,
+        max_items,
+        |c: &CampaignSummary| c.id,
+        |offset, limit| {
+            let page = campaigns_get_all(
+                http,
+                CampaignFilter {
+                    offset,
+                    limit,
+                    ..filter.clone()
+                },
+            )?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+pub fn campaigns_get_by_id(
+    http: &HttpClient,
+    campaign_id: u64,
+) -> Result<CampaignSummary, ApiError> {
+    let response: CampaignSummaryDto = http.make_request(
         HttpMethod::Get,
         &format!("/api/public/campaign/GetById?campaignId={}", campaign_id),
-        api_key,
+        RetryMode::Read,
         None::<&()>,
     )?;
 
     Ok(convert_campaign_summary(response))
 }
 
-pub fn campaigns_resume(api_key: &str, campaign_id: u64) -> Result<(), ApiError> {
-    make_request_empty(
+pub fn campaigns_resume(http: &HttpClient, campaign_id: u64) -> Result<(), ApiError> {
+    http.make_request_empty(
         HttpMethod::Post,
         &format!("/api/public/campaign/Resume?campaignId={}", campaign_id),
-        api_key,
+        RetryMode::Write,
         None::<&()>,
     )
 }
 
-pub fn campaigns_pause(api_key: &str, campaign_id: u64) -> Result<(), ApiError> {
-    make_request_empty(
+pub fn campaigns_pause(http: &HttpClient, campaign_id: u64) -> Result<(), ApiError> {
+    http.make_request_empty(
         HttpMethod::Post,
         &format!("/api/public/campaign/Pause?campaignId={}", campaign_id),
-        api_key,
+        RetryMode::Write,
         None::<&()>,
     )
 }
 
 pub fn campaigns_add_leads(
-    api_key: &str,
+    http: &HttpClient,
     payload: CampaignAddLeadsRequest,
 ) -> Result<u32, ApiError> {
     let payload_dto = CampaignAddLeadsRequestDto {
@@ -231,10 +466,10 @@ pub fn campaigns_add_leads(
             .collect(),
     };
 
-    let response: u32 = make_request(
+    let response: u32 = http.make_request(
         HttpMethod::Post,
         "/api/public/campaign/AddLeadsToCampaign",
-        api_key,
+        RetryMode::Write,
         Some(&payload_dto),
     )?;
 
@@ -242,48 +477,134 @@ pub fn campaigns_add_leads(
 }
 
 pub fn campaigns_add_leads_v2(
-    api_key: &str,
+    http: &HttpClient,
     payload: CampaignAddLeadsRequest,
 ) -> Result<CampaignAddLeadsV2Result, ApiError> {
-    let payload_dto = CampaignAddLeadsRequestDto {
-        campaign_id: payload.campaign_id,
-        account_lead_pairs: payload
-            .account_lead_pairs
-            .into_iter()
-            .map(|p| AccountLeadPairDto {
-                linked_in_account_id: p.linked_in_account_id,
-                lead: convert_lead(p.lead),
-            })
-            .collect(),
+    let campaign_id = payload.campaign_id;
+    let pairs: Vec<AccountLeadPairDto> = payload
+        .account_lead_pairs
+        .into_iter()
+        .map(|p| AccountLeadPairDto {
+            linked_in_account_id: p.linked_in_account_id,
+            lead: convert_lead(p.lead),
+        })
+        .collect();
+
+    add_leads_in_chunks(pairs, |account_lead_pairs| {
+        let payload_dto = CampaignAddLeadsRequestDto {
+            campaign_id,
+            account_lead_pairs,
+        };
+
+        http.make_request(
+            HttpMethod::Post,
+            "/api/public/campaign/AddLeadsToCampaignV2",
+            RetryMode::Write,
+            Some(&payload_dto),
+        )
+    })
+}
+
+pub fn campaigns_get_leads(
+    http: &HttpClient,
+    request: CampaignLeadsRequest,
+) -> Result<CampaignLeadsPage, ApiError> {
+    let request_dto = CampaignLeadsRequestDto {
+        campaign_id: request.campaign_id,
+        offset: request.offset,
+        limit: request.limit,
     };
 
-    let response: CampaignAddLeadsV2ResultDto = make_request(
+    let response: CampaignLeadsPageDto = http.make_request(
         HttpMethod::Post,
-        "/api/public/campaign/AddLeadsToCampaignV2",
-        api_key,
-        Some(&payload_dto),
+        "/api/public/campaign/GetLeadsFromCampaign",
+        RetryMode::Read,
+        Some(&request_dto),
     )?;
 
-    Ok(CampaignAddLeadsV2Result {
-        added_leads_count: response.added_leads_count,
-        updated_leads_count: response.updated_leads_count,
-        failed_leads_count: response.failed_leads_count,
+    Ok(CampaignLeadsPage {
+        total_count: response.total_count,
+        items: response
+            .items
+            .into_iter()
+            .map(|dto| CampaignLead {
+                status: map_lead_campaign_status(&dto.lead_campaign_status),
+                lead: convert_lead_dto(dto.lead),
+                linked_in_account_id: dto.linked_in_sender_id,
+            })
+            .collect(),
     })
 }
 
+/// Every lead in the campaign, from `request.offset` on; `request.limit` is
+/// ignored.
+pub fn campaigns_get_leads_all_pages(
+    http: &HttpClient,
+    request: CampaignLeadsRequest,
+    max_items: Option<u32>,
+) -> Result<Vec<CampaignLead>, ApiError> {
+    collect_pages(
+        request.offset,
+        max_items,
+        |l: &CampaignLead| l.lead.profile_url.clone(),
+        |offset, limit| {
+            let page = campaigns_get_leads(
+                http,
+                CampaignLeadsRequest {
+                    campaign_id: request.campaign_id,
+                    offset,
+                    limit,
+                },
+            )?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+/// Endpoints that act on one lead need its profile URL or LinkedIn id.
+fn require_lead_identifier(
+    lead_profile_url: &Option<String>,
+    lead_linked_in_id: &Option<String>,
+) -> Result<(), ApiError> {
+    if lead_profile_url.is_none() && lead_linked_in_id.is_none() {
+        return Err(api_error(
+            ApiErrorCode::BadRequest,
+            "Either lead-profile-url or lead-linked-in-id is required",
+        ));
+    }
+    Ok(())
+}
+
+pub fn campaigns_stop_lead(http: &HttpClient, request: StopLeadRequest) -> Result<(), ApiError> {
+    require_lead_identifier(&request.lead_profile_url, &request.lead_linked_in_id)?;
+
+    let request_dto = StopLeadRequestDto {
+        campaign_id: request.campaign_id,
+        lead_profile_url: request.lead_profile_url,
+        lead_linked_in_id: request.lead_linked_in_id,
+    };
+
+    http.make_request_empty(
+        HttpMethod::Post,
+        "/api/public/campaign/StopLeadInCampaign",
+        RetryMode::Write,
+        Some(&request_dto),
+    )
+}
+
 // -------- Lists --------
 
-pub fn lists_get_all(api_key: &str, filter: ListGetAllFilter) -> Result<ListPage, ApiError> {
+pub fn lists_get_all(http: &HttpClient, filter: ListGetAllFilter) -> Result<ListPage, ApiError> {
     let filter_dto = ListGetAllFilterDto {
         offset: filter.offset,
         limit: filter.limit,
         keyword: filter.keyword,
     };
 
-    let response: ListPageDto = make_request(
+    let response: ListPageDto = http.make_request(
         HttpMethod::Post,
         "/api/public/list/GetAll",
-        api_key,
+        RetryMode::Read,
         Some(&filter_dto),
     )?;
 
@@ -293,38 +614,49 @@ pub fn lists_get_all(api_key: &str, filter: ListGetAllFilter) -> Result<ListPage
         items: response
             .items
             .into_iter()
-            .map(|dto| ListSummary {
-                id: dto.id,
-                name: dto.name,
-                total_items_count: dto.total_items_count,
-                list_type: map_list_type(&dto.list_type),
-                creation_time: dto.creation_time,
-                campaign_ids: dto.campaign_ids,
-            })
+            .map(convert_list_summary)
             .collect(),
     })
 }
 
-pub fn lists_get_by_id(api_key: &str, list_id: u64) -> Result<ListSummary, ApiError> {
-    let response: ListSummaryDto = make_request(
+/// Every list matching `filter`, from `filter.offset` on; `filter.limit` is
+/// ignored.
+pub fn lists_get_all_pages(
+    http: &HttpClient,
+    filter: ListGetAllFilter,
+    max_items: Option<u32>,
+) -> Result<Vec<ListSummary>, ApiError> {
+    collect_pages(
+        filter.offset,
+        max_items,
+        |l: &ListSummary| l.id,
+        |offset, limit| {
+            let page = lists_get_all(
+                http,
+                ListGetAllFilter {
+                    offset,
+                    limit,
+                    ..filter.clone()
+                },
+            )?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+pub fn lists_get_by_id(http: &HttpClient, list_id: u64) -> Result<ListSummary, ApiError> {
+    let response: ListSummaryDto = http.make_request(
         HttpMethod::Get,
         &format!("/api/public/list/GetById?listId={}", list_id),
-        api_key,
+        RetryMode::Read,
         None::<&()>,
     )?;
 
-    Ok(ListSummary {
-        id: response.id,
-        name: response.name,
-        total_items_count: response.total_items_count,
-        list_type: map_list_type(&response.list_type),
-        creation_time: response.creation_time,
-        campaign_ids: response.campaign_ids,
-    })
+    Ok(convert_list_summary(response))
 }
 
 pub fn lists_get_leads(
-    api_key: &str,
+    http: &HttpClient,
     list_id: u64,
     offset: u32,
     limit: u32,
@@ -337,10 +669,10 @@ pub fn lists_get_leads(
         keyword,
     };
 
-    let response: ListLeadsPageDto = make_request(
+    let response: ListLeadsPageDto = http.make_request(
         HttpMethod::Post,
         "/api/public/list/GetLeadsFromList",
-        api_key,
+        RetryMode::Read,
         Some(&request_dto),
     )?;
 
@@ -351,60 +683,165 @@ pub fn lists_get_leads(
     })
 }
 
-pub fn lists_add_leads(api_key: &str, list_id: u64, leads: Vec<Lead>) -> Result<(), ApiError> {
+/// Every lead in the list, deduplicated by profile URL.
+pub fn lists_get_leads_all_pages(
+    http: &HttpClient,
+    list_id: u64,
+    keyword: Option<String>,
+    max_items: Option<u32>,
+) -> Result<Vec<Lead>, ApiError> {
+    collect_pages(
+        0,
+        max_items,
+        |l: &Lead| l.profile_url.clone(),
+        |offset, limit| {
+            let page = lists_get_leads(http, list_id, offset, limit, keyword.clone())?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+pub fn lists_add_leads(http: &HttpClient, list_id: u64, leads: Vec<Lead>) -> Result<(), ApiError> {
     let request_dto = ListAddLeadsRequestDto {
         list_id,
         leads: leads.into_iter().map(convert_lead).collect(),
     };
 
-    make_request_empty(
+    http.make_request_empty(
         HttpMethod::Post,
         "/api/public/list/AddLeadsToList",
-        api_key,
+        RetryMode::Write,
         Some(&request_dto),
     )
 }
 
 pub fn lists_add_leads_v2(
-    api_key: &str,
+    http: &HttpClient,
     list_id: u64,
     leads: Vec<Lead>,
 ) -> Result<CampaignAddLeadsV2Result, ApiError> {
-    let request_dto = ListAddLeadsRequestDto {
+    let leads: Vec<LeadDto> = leads.into_iter().map(convert_lead).collect();
+
+    add_leads_in_chunks(leads, |leads| {
+        let request_dto = ListAddLeadsRequestDto { list_id, leads };
+
+        http.make_request(
+            HttpMethod::Post,
+            "/api/public/list/AddLeadsToListV2",
+            RetryMode::Write,
+            Some(&request_dto),
+        )
+    })
+}
+
+pub fn lists_create_empty(
+    http: &HttpClient,
+    request: CreateListRequest,
+) -> Result<ListSummary, ApiError> {
+    let list_type = list_type_to_string(&request.list_type).ok_or_else(|| {
+        api_error(
+            ApiErrorCode::BadRequest,
+            "List type must be leads or companies",
+        )
+    })?;
+
+    let request_dto = CreateEmptyListRequestDto {
+        name: request.name,
+        list_type,
+    };
+
+    let response: CreatedListDto = http.make_request(
+        HttpMethod::Post,
+        "/api/public/list/CreateEmptyList",
+        RetryMode::Never,
+        Some(&request_dto),
+    )?;
+
+    Ok(ListSummary {
+        id: response.id,
+        name: response.name,
+        total_items_count: response.total_items_count,
+        list_type: map_list_type(&response.list_type),
+        creation_time: response.creation_time,
+        campaign_ids: response.campaign_ids,
+    })
+}
+
+pub fn lists_get_companies(
+    http: &HttpClient,
+    list_id: u64,
+    offset: u32,
+    limit: u32,
+    keyword: Option<String>,
+) -> Result<CompanyPage, ApiError> {
+    let request_dto = ListGetLeadsRequestDto {
         list_id,
-        leads: leads.into_iter().map(convert_lead).collect(),
+        offset,
+        limit,
+        keyword,
     };
 
-    let response: CampaignAddLeadsV2ResultDto = make_request(
+    let response: CompanyPageDto = http.make_request(
         HttpMethod::Post,
-        "/api/public/list/AddLeadsToListV2",
-        api_key,
+        "/api/public/list/GetCompaniesFromList",
+        RetryMode::Read,
         Some(&request_dto),
     )?;
 
-    Ok(CampaignAddLeadsV2Result {
-        added_leads_count: response.added_leads_count,
-        updated_leads_count: response.updated_leads_count,
-        failed_leads_count: response.failed_leads_count,
+    Ok(CompanyPage {
+        total_count: response.total_count,
+        items: response
+            .items
+            .into_iter()
+            .map(|dto| Company {
+                name: dto.name,
+                linked_in_url: dto.linked_in_url,
+                industry: dto.industry,
+                company_size: dto.company_size,
+                location: dto.location,
+            })
+            .collect(),
     })
 }
 
-pub fn lists_delete_leads(api_key: &str, request: ListLeadDeleteRequest) -> Result<(), ApiError> {
+/// Every company in the list, deduplicated by LinkedIn URL (or name when
+/// the URL is missing).
+pub fn lists_get_companies_all_pages(
+    http: &HttpClient,
+    list_id: u64,
+    keyword: Option<String>,
+    max_items: Option<u32>,
+) -> Result<Vec<Company>, ApiError> {
+    collect_pages(
+        0,
+        max_items,
+        |c: &Company| c.linked_in_url.clone().unwrap_or_else(|| c.name.clone()),
+        |offset, limit| {
+            let page = lists_get_companies(http, list_id, offset, limit, keyword.clone())?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+pub fn lists_delete_leads(
+    http: &HttpClient,
+    request: ListLeadDeleteRequest,
+) -> Result<(), ApiError> {
     let request_dto = ListLeadDeleteRequestDto {
         list_id: request.list_id,
         lead_member_ids: request.lead_member_ids,
     };
 
-    make_request_empty(
+    http.make_request_empty(
         HttpMethod::Delete,
         "/api/public/list/DeleteLeadsFromList",
-        api_key,
+        RetryMode::Write,
         Some(&request_dto),
     )
 }
 
 pub fn lists_delete_leads_by_profile_url(
-    api_key: &str,
+    http: &HttpClient,
     request: ListLeadDeleteByProfileUrlRequest,
 ) -> Result<ListLeadDeleteByProfileUrlResponse, ApiError> {
     let request_dto = ListLeadDeleteByProfileUrlRequestDto {
@@ -412,10 +849,10 @@ pub fn lists_delete_leads_by_profile_url(
         profile_urls: request.profile_urls,
     };
 
-    let response: ListLeadDeleteByProfileUrlResponseDto = make_request(
+    let response: ListLeadDeleteByProfileUrlResponseDto = http.make_request(
         HttpMethod::Delete,
         "/api/public/list/DeleteLeadsFromListByProfileUrl",
-        api_key,
+        RetryMode::Write,
         Some(&request_dto),
     )?;
 
@@ -426,13 +863,13 @@ pub fn lists_delete_leads_by_profile_url(
 
 // -------- Lead & Tags --------
 
-pub fn lead_get(api_key: &str, profile_url: String) -> Result<Lead, ApiError> {
+pub fn lead_get(http: &HttpClient, profile_url: String) -> Result<Lead, ApiError> {
     let request_dto = LeadGetRequestDto { profile_url };
 
-    let response: LeadDto = make_request(
+    let response: LeadDto = http.make_request(
         HttpMethod::Post,
         "/api/public/lead/GetLead",
-        api_key,
+        RetryMode::Read,
         Some(&request_dto),
     )?;
 
@@ -440,10 +877,10 @@ pub fn lead_get(api_key: &str, profile_url: String) -> Result<Lead, ApiError> {
 }
 
 pub fn lead_get_lists(
-    api_key: &str,
+    http: &HttpClient,
     request: LeadListsRequest,
 ) -> Result<LeadListsResponse, ApiError> {
-    let request_dto = LeadListsRequestDto {
+    let request_dto = LeadLookupRequestDto {
         email: request.email,
         linkedin_id: request.linkedin_id,
         profile_url: request.profile_url,
@@ -451,10 +888,10 @@ pub fn lead_get_lists(
         limit: request.limit,
     };
 
-    let response: LeadListsResponseDto = make_request(
+    let response: LeadListsResponseDto = http.make_request(
         HttpMethod::Post,
         "/api/public/list/GetListsForLead",
-        api_key,
+        RetryMode::Read,
         Some(&request_dto),
     )?;
 
@@ -472,13 +909,97 @@ pub fn lead_get_lists(
     })
 }
 
-pub fn lead_get_tags(api_key: &str, profile_url: String) -> Result<LeadTagsResponse, ApiError> {
+/// Every list the lead belongs to, from `request.offset` on;
+/// `request.limit` is ignored.
+pub fn lead_get_lists_all_pages(
+    http: &HttpClient,
+    request: LeadListsRequest,
+    max_items: Option<u32>,
+) -> Result<Vec<LeadListSummary>, ApiError> {
+    collect_pages(
+        request.offset,
+        max_items,
+        |l: &LeadListSummary| l.list_id,
+        |offset, limit| {
+            let page = lead_get_lists(
+                http,
+                LeadListsRequest {
+                    offset,
+                    limit,
+                    ..request.clone()
+                },
+            )?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+pub fn lead_get_campaigns(
+    http: &HttpClient,
+    request: LeadCampaignsRequest,
+) -> Result<LeadCampaignsResponse, ApiError> {
+    let request_dto = LeadLookupRequestDto {
+        email: request.email,
+        linkedin_id: request.linkedin_id,
+        profile_url: request.profile_url,
+        offset: request.offset,
+        limit: request.limit,
+    };
+
+    let response: LeadCampaignsResponseDto = http.make_request(
+        HttpMethod::Post,
+        "/api/public/lead/GetCampaignsForLead",
+        RetryMode::Read,
+        Some(&request_dto),
+    )?;
+
+    Ok(LeadCampaignsResponse {
+        total_count: response.total_count,
+        items: response
+            .items
+            .into_iter()
+            .map(|dto| LeadCampaignSummary {
+                campaign_id: dto.campaign_id,
+                campaign_name: dto.campaign_name,
+                campaign_status: map_campaign_status(&dto.campaign_status),
+                lead_status: map_lead_campaign_status(&dto.lead_campaign_status),
+            })
+            .collect(),
+    })
+}
+
+/// Every campaign the lead is enrolled in, from `request.offset` on;
+/// `request.limit` is ignored.
+pub fn lead_get_campaigns_all_pages(
+    http: &HttpClient,
+    request: LeadCampaignsRequest,
+    max_items: Option<u32>,
+) -> Result<Vec<LeadCampaignSummary>, ApiError> {
+    collect_pages(
+        request.offset,
+        max_items,
+        |c: &LeadCampaignSummary| c.campaign_id,
+        |offset, limit| {
+            let page = lead_get_campaigns(
+                http,
+                LeadCampaignsRequest {
+                    offset,
+                    limit,
+                    ..request.clone()
+                },
+            )?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+pub fn lead_get_tags(http: &HttpClient, profile_url: String) -> Result<LeadTagsResponse, ApiError> {
     let request_dto = LeadGetRequestDto { profile_url };
 
-    let response: LeadTagsResponseDto = make_request(
+    let response: LeadTagsResponseDto = http.make_request(
         HttpMethod::Post,
         "/api/public/lead/GetTags",
-        api_key,
+        RetryMode::Read,
         Some(&request_dto),
     )?;
 
@@ -488,7 +1009,7 @@ pub fn lead_get_tags(api_key: &str, profile_url: String) -> Result<LeadTagsRespo
 }
 
 pub fn lead_replace_tags(
-    api_key: &str,
+    http: &HttpClient,
     request: LeadReplaceTagsRequest,
 ) -> Result<LeadReplaceTagsResponse, ApiError> {
     let request_dto = LeadReplaceTagsRequestDto {
@@ -498,10 +1019,10 @@ pub fn lead_replace_tags(
         create_tag_if_not_existing: request.create_tag_if_not_existing,
     };
 
-    let response: LeadReplaceTagsResponseDto = make_request(
+    let response: LeadReplaceTagsResponseDto = http.make_request(
         HttpMethod::Post,
         "/api/public/lead/ReplaceTags",
-        api_key,
+        RetryMode::Write,
         Some(&request_dto),
     )?;
 
@@ -510,10 +1031,73 @@ pub fn lead_replace_tags(
     })
 }
 
+pub fn lead_add_tags(
+    http: &HttpClient,
+    request: LeadAddTagsRequest,
+) -> Result<LeadAddTagsResponse, ApiError> {
+    require_lead_identifier(&request.lead_profile_url, &request.lead_linked_in_id)?;
+
+    let request_dto = LeadAddTagsRequestDto {
+        lead_profile_url: request.lead_profile_url,
+        lead_linked_in_id: request.lead_linked_in_id,
+        tags: request.tags,
+        create_tag_if_not_existing: request.create_tag_if_not_existing,
+    };
+
+    let response: LeadAddTagsResponseDto = http.make_request(
+        HttpMethod::Post,
+        "/api/public/lead/AddTags",
+        RetryMode::Write,
+        Some(&request_dto),
+    )?;
+
+    Ok(LeadAddTagsResponse {
+        new_assigned_tags: response.new_assigned_tags,
+    })
+}
+
+pub fn lead_remove_tags(http: &HttpClient, request: LeadRemoveTagsRequest) -> Result<(), ApiError> {
+    require_lead_identifier(&request.lead_profile_url, &request.lead_linked_in_id)?;
+
+    let request_dto = LeadRemoveTagsRequestDto {
+        lead_profile_url: request.lead_profile_url,
+        lead_linked_in_id: request.lead_linked_in_id,
+        tags: request.tags,
+    };
+
+    http.make_request_empty(
+        HttpMethod::Post,
+        "/api/public/lead/DeleteTags",
+        RetryMode::Write,
+        Some(&request_dto),
+    )
+}
+
+/// Every tag in the workspace, up to `max_items`.
+pub fn tags_get_all(http: &HttpClient, max_items: Option<u32>) -> Result<Vec<String>, ApiError> {
+    collect_pages(
+        0,
+        max_items,
+        |tag: &String| tag.clone(),
+        |offset, limit| {
+            let response: TagPageDto = http.make_request(
+                HttpMethod::Post,
+                "/api/public/tag/GetAll",
+                RetryMode::Read,
+                Some(&TagsGetAllRequestDto { offset, limit }),
+            )?;
+            Ok((
+                response.total_count,
+                response.items.into_iter().map(|t| t.name).collect(),
+            ))
+        },
+    )
+}
+
 // -------- Inbox --------
 
 pub fn inbox_get_conversations_v2(
-    api_key: &str,
+    http: &HttpClient,
     request: InboxGetConversationsRequest,
 ) -> Result<InboxConversationPage, ApiError> {
     let request_dto = InboxGetConversationsRequestDto {
@@ -529,10 +1113,10 @@ pub fn inbox_get_conversations_v2(
         limit: request.limit,
     };
 
-    let response: InboxConversationPageDto = make_request(
+    let response: InboxConversationPageDto = http.make_request(
         HttpMethod::Post,
         "/api/public/inbox/GetConversationsV2",
-        api_key,
+        RetryMode::Read,
         Some(&request_dto),
     )?;
 
@@ -553,7 +1137,35 @@ pub fn inbox_get_conversations_v2(
     })
 }
 
-pub fn inbox_send_message(api_key: &str, request: InboxSendMessageRequest) -> Result<(), ApiError> {
+/// Every conversation matching `request.filters`, from `request.offset` on;
+/// `request.limit` is ignored.
+pub fn inbox_get_conversations_v2_all_pages(
+    http: &HttpClient,
+    request: InboxGetConversationsRequest,
+    max_items: Option<u32>,
+) -> Result<Vec<InboxConversationSummary>, ApiError> {
+    collect_pages(
+        request.offset,
+        max_items,
+        |c: &InboxConversationSummary| c.conversation_id.clone(),
+        |offset, limit| {
+            let page = inbox_get_conversations_v2(
+                http,
+                InboxGetConversationsRequest {
+                    filters: request.filters.clone(),
+                    offset,
+                    limit,
+                },
+            )?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+pub fn inbox_send_message(
+    http: &HttpClient,
+    request: InboxSendMessageRequest,
+) -> Result<(), ApiError> {
     let request_dto = InboxSendMessageRequestDto {
         message: request.message,
         subject: request.subject,
@@ -561,18 +1173,69 @@ pub fn inbox_send_message(api_key: &str, request: InboxSendMessageRequest) -> Re
         linked_in_account_id: request.linked_in_account_id,
     };
 
-    make_request_empty(
+    http.make_request_empty(
         HttpMethod::Post,
         "/api/public/inbox/SendMessage",
-        api_key,
+        RetryMode::Never,
         Some(&request_dto),
     )
 }
 
+pub fn inbox_get_conversation(
+    http: &HttpClient,
+    linked_in_account_id: u32,
+    conversation_id: String,
+) -> Result<ConversationThread, ApiError> {
+    let mut response: ChatroomDto = http.make_request(
+        HttpMethod::Get,
+        &format!(
+            "/api/public/inbox/GetChatroom/{}/{}",
+            linked_in_account_id,
+            encode_path_segment(&conversation_id)
+        ),
+        RetryMode::Read,
+        None::<&()>,
+    )?;
+
+    // Sort on the parsed instant: fractional seconds and offsets make string
+    // order wrong. If any timestamp is unreadable, keep the API's order.
+    let instants: Option<Vec<u64>> = response
+        .messages
+        .iter()
+        .map(|m| clock::parse_timestamp_ms(&m.created_at))
+        .collect();
+    if let Some(instants) = instants {
+        let mut keyed: Vec<_> = instants.into_iter().zip(response.messages).collect();
+        keyed.sort_by_key(|(instant, _)| *instant);
+        response.messages = keyed.into_iter().map(|(_, message)| message).collect();
+    }
+
+    Ok(ConversationThread {
+        conversation_id: response.conversation_id,
+        linked_in_account_id: response.linked_in_account_id,
+        lead_profile_url: response.correspondent_profile.and_then(|p| p.profile_url),
+        messages: response
+            .messages
+            .into_iter()
+            .map(|m| ConversationMessage {
+                sender: map_message_sender(&m.sender),
+                body: m.body,
+                subject: m.subject,
+                created_at: m.created_at,
+                message_type: if m.is_in_mail {
+                    MessageType::InMail
+                } else {
+                    MessageType::Regular
+                },
+            })
+            .collect(),
+    })
+}
+
 // -------- LinkedIn Accounts --------
 
 pub fn li_account_get_all(
-    api_key: &str,
+    http: &HttpClient,
     filter: LiAccountFilter,
 ) -> Result<LiAccountPage, ApiError> {
     let filter_dto = LiAccountFilterDto {
@@ -581,10 +1244,10 @@ pub fn li_account_get_all(
         keyword: filter.keyword,
     };
 
-    let response: LiAccountPageDto = make_request(
+    let response: LiAccountPageDto = http.make_request(
         HttpMethod::Post,
         "/api/public/li_account/GetAll",
-        api_key,
+        RetryMode::Read,
         Some(&filter_dto),
     )?;
 
@@ -593,25 +1256,135 @@ pub fn li_account_get_all(
         items: response
             .items
             .into_iter()
-            .map(|dto| LiAccountSummary {
-                id: dto.id,
-                email_address: dto.email_address,
+            .map(convert_li_account_summary)
+            .collect(),
+    })
+}
+
+/// Every LinkedIn account matching `filter`, from `filter.offset` on;
+/// `filter.limit` is ignored.
+pub fn li_account_get_all_pages(
+    http: &HttpClient,
+    filter: LiAccountFilter,
+    max_items: Option<u32>,
+) -> Result<Vec<LiAccountSummary>, ApiError> {
+    collect_pages(
+        filter.offset,
+        max_items,
+        |a: &LiAccountSummary| a.id,
+        |offset, limit| {
+            let page = li_account_get_all(
+                http,
+                LiAccountFilter {
+                    offset,
+                    limit,
+                    ..filter.clone()
+                },
+            )?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+pub fn li_account_get_by_id(
+    http: &HttpClient,
+    linked_in_account_id: u32,
+) -> Result<LiAccountDetail, ApiError> {
+    let response: LiAccountDetailDto = http.make_request(
+        HttpMethod::Get,
+        &format!(
+            "/api/public/li_account/GetById?linkedInAccountId={}",
+            linked_in_account_id
+        ),
+        RetryMode::Read,
+        None::<&()>,
+    )?;
+
+    let limits = response.daily_limits;
+    Ok(LiAccountDetail {
+        summary: convert_li_account_summary(response.summary),
+        profile_url: response.profile_url,
+        subscription_type: response.subscription_type,
+        daily_limits: LiAccountDailyLimits {
+            connection_requests: limits.connection_requests,
+            messages: limits.messages,
+            inmails: limits.inmails,
+            profile_views: limits.profile_views,
+            follows: limits.follows,
+        },
+        proxy_type: response.proxy_type,
+        proxy_location: response.proxy_location,
+        session_status: response.session_status,
+        campaign_ids: response.campaign_ids,
+    })
+}
+
+pub fn li_account_get_network(
+    http: &HttpClient,
+    request: MyNetworkRequest,
+) -> Result<NetworkConnectionPage, ApiError> {
+    let request_dto = MyNetworkRequestDto {
+        sender_id: request.linked_in_account_id,
+        page_number: request.page_number,
+        page_size: request.page_size.min(MAX_PAGE_SIZE),
+    };
+
+    let response: NetworkConnectionPageDto = http.make_request(
+        HttpMethod::Post,
+        "/api/public/MyNetwork/GetMyNetworkForSender",
+        RetryMode::Read,
+        Some(&request_dto),
+    )?;
+
+    Ok(NetworkConnectionPage {
+        total_count: response.total_count,
+        items: response
+            .items
+            .into_iter()
+            .map(|dto| NetworkConnection {
+                profile_url: dto.profile_url,
                 first_name: dto.first_name,
                 last_name: dto.last_name,
-                is_active: dto.is_active,
-                active_campaigns: dto.active_campaigns,
-                auth_is_valid: dto.auth_is_valid,
-                is_valid_navigator: dto.is_valid_navigator,
-                is_valid_recruiter: dto.is_valid_recruiter,
+                headline: dto.headline,
+                connected_at: dto.connected_at,
             })
             .collect(),
     })
 }
 
+/// Every connection of the sender, deduplicated by profile URL.
+pub fn li_account_get_network_all_pages(
+    http: &HttpClient,
+    linked_in_account_id: u32,
+    max_items: Option<u32>,
+) -> Result<Vec<NetworkConnection>, ApiError> {
+    // Pages are addressed by number, so always request full pages and let
+    // `collect_pages` trim the excess; offsets then stay multiples of the
+    // page size.
+    collect_pages(
+        0,
+        max_items,
+        |c: &NetworkConnection| c.profile_url.clone(),
+        |offset, _limit| {
+            let page = li_account_get_network(
+                http,
+                MyNetworkRequest {
+                    linked_in_account_id,
+                    page_number: offset / MAX_PAGE_SIZE,
+                    page_size: MAX_PAGE_SIZE,
+                },
+            )?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
 
 // -------- Webhooks --------
 
-pub fn webhooks_create(api_key: &str, request: CreateWebhookRequest) -> Result<Webhook, ApiError> {
+pub fn webhooks_create(
+    http: &HttpClient,
+    request: CreateWebhookRequest,
+) -> Result<Webhook, ApiError> {
     let request_dto = CreateWebhookRequestDto {
         webhook_name: request.webhook_name,
         webhook_url: request.webhook_url,
@@ -620,10 +1393,10 @@ pub fn webhooks_create(api_key: &str, request: CreateWebhookRequest) -> Result<W
         is_active: request.is_active,
     };
 
-    let response: WebhookDto = make_request(
+    let response: WebhookDto = http.make_request(
         HttpMethod::Post,
         "/api/public/webhooks/CreateWebhook",
-        api_key,
+        RetryMode::Never,
         Some(&request_dto),
     )?;
 
@@ -637,14 +1410,14 @@ pub fn webhooks_create(api_key: &str, request: CreateWebhookRequest) -> Result<W
     })
 }
 
-pub fn webhooks_get_by_id(api_key: &str, webhook_id: u64) -> Result<Webhook, ApiError> {
-    let response: WebhookDto = make_request(
+pub fn webhooks_get_by_id(http: &HttpClient, webhook_id: u64) -> Result<Webhook, ApiError> {
+    let response: WebhookDto = http.make_request(
         HttpMethod::Get,
         &format!(
             "/api/public/webhooks/GetWebhookById?webhookId={}",
             webhook_id
         ),
-        api_key,
+        RetryMode::Read,
         None::<&()>,
     )?;
 
@@ -658,16 +1431,19 @@ pub fn webhooks_get_by_id(api_key: &str, webhook_id: u64) -> Result<Webhook, Api
     })
 }
 
-pub fn webhooks_get_all(api_key: &str, filter: GetWebhooksFilter) -> Result<WebhookPage, ApiError> {
+pub fn webhooks_get_all(
+    http: &HttpClient,
+    filter: GetWebhooksFilter,
+) -> Result<WebhookPage, ApiError> {
     let filter_dto = GetWebhooksFilterDto {
         offset: filter.offset,
         limit: filter.limit,
     };
 
-    let response: WebhookPageDto = make_request(
+    let response: WebhookPageDto = http.make_request(
         HttpMethod::Post,
         "/api/public/webhooks/GetAllWebhooks",
-        api_key,
+        RetryMode::Read,
         Some(&filter_dto),
     )?;
 
@@ -689,14 +1465,1383 @@ pub fn webhooks_get_all(api_key: &str, filter: GetWebhooksFilter) -> Result<Webh
     })
 }
 
-pub fn webhooks_delete(api_key: &str, webhook_id: u64) -> Result<(), ApiError> {
-    make_request_empty(
+/// Every webhook, from `filter.offset` on; `filter.limit` is ignored.
+pub fn webhooks_get_all_pages(
+    http: &HttpClient,
+    filter: GetWebhooksFilter,
+    max_items: Option<u32>,
+) -> Result<Vec<Webhook>, ApiError> {
+    collect_pages(
+        filter.offset,
+        max_items,
+        |w: &Webhook| w.id,
+        |offset, limit| {
+            let page = webhooks_get_all(http, GetWebhooksFilter { offset, limit })?;
+            Ok((page.total_count, page.items))
+        },
+    )
+}
+
+pub fn webhooks_update(
+    http: &HttpClient,
+    webhook_id: u64,
+    request: UpdateWebhookRequest,
+) -> Result<Option<Webhook>, ApiError> {
+    let request_dto = UpdateWebhookRequestDto {
+        webhook_name: request.webhook_name,
+        webhook_url: request.webhook_url,
+        event_type: request
+            .event_type
+            .as_ref()
+            .map(webhook_event_type_to_string),
+        campaign_ids: request.campaign_ids,
+        is_active: request.is_active,
+    };
+
+    if request_dto.webhook_name.is_none()
+        && request_dto.webhook_url.is_none()
+        && request_dto.event_type.is_none()
+        && request_dto.campaign_ids.is_none()
+        && request_dto.is_active.is_none()
+    {
+        return Err(api_error(
+            ApiErrorCode::BadRequest,
+            "Webhook update sets no fields",
+        ));
+    }
+
+    http.make_request_empty(
+        HttpMethod::Patch,
+        &format!(
+            "/api/public/webhooks/UpdateWebhook?webhookId={}",
+            webhook_id
+        ),
+        RetryMode::Write,
+        Some(&request_dto),
+    )?;
+
+    // The update response carries no webhook, so read back the stored state.
+    // The change is already applied, so a failed read is not an error.
+    Ok(webhooks_get_by_id(http, webhook_id).ok())
+}
+
+pub fn webhooks_delete(http: &HttpClient, webhook_id: u64) -> Result<(), ApiError> {
+    http.make_request_empty(
         HttpMethod::Delete,
         &format!(
             "/api/public/webhooks/DeleteWebhook?webhookId={}",
             webhook_id
         ),
-        api_key,
+        RetryMode::Write,
         None::<&()>,
     )
 }
+
+/// Diffs `desired` against the existing webhooks in scope and, unless
+/// `options.dry_run`, applies the resulting plan. Creates and updates run
+/// before deletes.
+pub fn webhooks_ensure(
+    http: &HttpClient,
+    desired: Vec<CreateWebhookRequest>,
+    options: WebhookEnsureOptions,
+) -> Result<WebhookReconcileResult, ApiError> {
+    let dry_run = options.dry_run;
+    let existing = webhooks_get_all_pages(
+        http,
+        GetWebhooksFilter {
+            offset: 0,
+            limit: MAX_PAGE_SIZE,
+        },
+        None,
+    )?;
+    let plan = plan_webhooks(desired, existing, &options)?;
+
+    let mut result = WebhookReconcileResult {
+        plan,
+        applied: !dry_run,
+        created: Vec::new(),
+        updated: Vec::new(),
+        deleted: Vec::new(),
+        errors: Vec::new(),
+    };
+    if dry_run {
+        return Ok(result);
+    }
+
+    for request in &result.plan.creates {
+        match webhooks_create(http, request.clone()) {
+            Ok(webhook) => result.created.push(webhook),
+            Err(error) => result.errors.push(WebhookChangeError {
+                webhook_name: request.webhook_name.clone(),
+                error,
+            }),
+        }
+    }
+
+    for update in &result.plan.updates {
+        match webhooks_update(http, update.current.id, update.changes.clone()) {
+            Ok(webhook) => result
+                .updated
+                .push(webhook.unwrap_or_else(|| apply_changes(&update.current, &update.changes))),
+            Err(error) => result.errors.push(WebhookChangeError {
+                webhook_name: update.current.webhook_name.clone(),
+                error,
+            }),
+        }
+    }
+
+    for webhook in &result.plan.deletes {
+        match webhooks_delete(http, webhook.id) {
+            Ok(()) => result.deleted.push(webhook.id),
+            Err(error) => result.errors.push(WebhookChangeError {
+                webhook_name: webhook.webhook_name.clone(),
+                error,
+            }),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Matches desired webhooks to existing ones by name; the lowest id wins
+/// when several existing webhooks share a name. Webhooks outside
+/// `options.name_prefix` are never touched, and unmatched ones are only
+/// deleted with `options.prune`.
+fn plan_webhooks(
+    desired: Vec<CreateWebhookRequest>,
+    mut existing: Vec<Webhook>,
+    options: &WebhookEnsureOptions,
+) -> Result<WebhookPlan, ApiError> {
+    let prefix = options.name_prefix.as_deref().unwrap_or("");
+
+    let mut names = HashSet::new();
+    for request in &desired {
+        if request.webhook_name.trim().is_empty() {
+            return Err(api_error(
+                ApiErrorCode::BadRequest,
+                "Desired webhook has an empty name",
+            ));
+        }
+        if !request.webhook_name.starts_with(prefix) {
+            return Err(api_error(
+                ApiErrorCode::BadRequest,
+                &format!(
+                    "Desired webhook name '{}' does not start with '{}'",
+                    request.webhook_name, prefix
+                ),
+            ));
+        }
+        if !names.insert(request.webhook_name.as_str()) {
+            return Err(api_error(
+                ApiErrorCode::BadRequest,
+                &format!("Duplicate desired webhook name '{}'", request.webhook_name),
+            ));
+        }
+    }
+
+    existing.sort_by_key(|w| w.id);
+
+    let mut matched: Vec<Option<Webhook>> = vec![None; desired.len()];
+    let mut deletes = Vec::new();
+    let mut ignored = Vec::new();
+    for webhook in existing {
+        if !webhook.webhook_name.starts_with(prefix) {
+            ignored.push(webhook);
+            continue;
+        }
+        match desired
+            .iter()
+            .position(|d| d.webhook_name == webhook.webhook_name)
+        {
+            Some(i) if matched[i].is_none() => matched[i] = Some(webhook),
+            _ if options.prune => deletes.push(webhook),
+            _ => ignored.push(webhook),
+        }
+    }
+
+    let mut plan = WebhookPlan {
+        creates: Vec::new(),
+        updates: Vec::new(),
+        deletes,
+        unchanged: Vec::new(),
+        ignored,
+    };
+    for (request, current) in desired.into_iter().zip(matched) {
+        let Some(current) = current else {
+            plan.creates.push(request);
+            continue;
+        };
+
+        let changes = webhook_changes(&current, request);
+        if changes.webhook_url.is_none()
+            && changes.event_type.is_none()
+            && changes.campaign_ids.is_none()
+            && changes.is_active.is_none()
+        {
+            plan.unchanged.push(current);
+        } else {
+            plan.updates.push(WebhookPlanUpdate { current, changes });
+        }
+    }
+
+    Ok(plan)
+}
+
+/// `current` with `changes` applied, for updates that could not be read back.
+fn apply_changes(current: &Webhook, changes: &UpdateWebhookRequest) -> Webhook {
+    let changes = changes.clone();
+    Webhook {
+        id: current.id,
+        webhook_name: changes
+            .webhook_name
+            .unwrap_or_else(|| current.webhook_name.clone()),
+        webhook_url: changes
+            .webhook_url
+            .unwrap_or_else(|| current.webhook_url.clone()),
+        event_type: changes
+            .event_type
+            .unwrap_or_else(|| current.event_type.clone()),
+        campaign_ids: changes
+            .campaign_ids
+            .unwrap_or_else(|| current.campaign_ids.clone()),
+        is_active: changes.is_active.unwrap_or(current.is_active),
+    }
+}
+
+/// Fields of `desired` that differ from `current`. Campaign ids are compared
+/// as sets.
+fn webhook_changes(current: &Webhook, desired: CreateWebhookRequest) -> UpdateWebhookRequest {
+    let mut current_campaigns = current.campaign_ids.clone();
+    current_campaigns.sort_unstable();
+    current_campaigns.dedup();
+    let mut desired_campaigns = desired.campaign_ids.clone();
+    desired_campaigns.sort_unstable();
+    desired_campaigns.dedup();
+
+    UpdateWebhookRequest {
+        webhook_name: None,
+        webhook_url: (desired.webhook_url != current.webhook_url).then_some(desired.webhook_url),
+        event_type: (webhook_event_type_to_string(&desired.event_type)
+            != webhook_event_type_to_string(&current.event_type))
+        .then_some(desired.event_type),
+        campaign_ids: (desired_campaigns != current_campaigns).then_some(desired.campaign_ids),
+        is_active: (desired.is_active != current.is_active).then_some(desired.is_active),
+    }
+}
+
+/// Parses a webhook delivery body into a typed event.
+pub fn webhooks_parse_event(body: &str) -> Result<WebhookEvent, ApiError> {
+    let dto: WebhookEventDto = serde_json::from_str(body).map_err(|e| {
+        api_error(
+            ApiErrorCode::Decode,
+            &format!("Invalid webhook payload: {}", e),
+        )
+    })?;
+
+    if dto.event_type.trim().is_empty() {
+        return Err(api_error(
+            ApiErrorCode::Decode,
+            "Invalid webhook payload: empty event_type",
+        ));
+    }
+
+    Ok(WebhookEvent {
+        event_id: dto.event_id.and_then(json_id),
+        event_type: map_webhook_event_type(&dto.event_type),
+        timestamp: dto.timestamp,
+        lead: dto.lead.map(|lead| WebhookLead {
+            id: lead.id.and_then(json_id),
+            profile_url: lead.profile_url,
+            first_name: lead.first_name,
+            last_name: lead.last_name,
+            full_name: lead.full_name,
+            company_name: lead.company_name,
+            position: lead.position,
+            location: lead.location,
+            email_address: lead.email_address,
+            tags: lead.tags.unwrap_or_default(),
+        }),
+        sender: dto.sender.map(|sender| WebhookSender {
+            id: sender.id,
+            first_name: sender.first_name,
+            last_name: sender.last_name,
+            full_name: sender.full_name,
+            email_address: sender.email_address,
+            profile_url: sender.profile_url,
+        }),
+        campaign: dto.campaign.map(|campaign| WebhookCampaign {
+            id: campaign.id,
+            name: campaign.name,
+            status: campaign.status,
+        }),
+        conversation_id: dto.conversation_id,
+        messages: dto
+            .recent_messages
+            .unwrap_or_default()
+            .into_iter()
+            .map(|message| WebhookMessage {
+                content: message.message.unwrap_or_default(),
+                sent_at: message.creation_time,
+                is_reply: message.is_reply.unwrap_or(false),
+            })
+            .collect(),
+        is_inmail: dto.is_inmail.unwrap_or(false),
+    })
+}
+
+/// An id sent as either a JSON string or a number.
+fn json_id(id: serde_json::Value) -> Option<String> {
+    match id {
+        serde_json::Value::String(s) => Some(s),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+// -------- Stats --------
+
+pub fn stats_get_overall(
+    http: &HttpClient,
+    filter: StatsFilter,
+) -> Result<CampaignStats, ApiError> {
+    let filter_dto = StatsFilterDto {
+        account_ids: filter.account_ids,
+        campaign_ids: filter.campaign_ids,
+        start_date: filter.start_date,
+        end_date: filter.end_date,
+    };
+
+    let response: StatsResponseDto = http.make_request(
+        HttpMethod::Post,
+        "/api/public/stats/GetOverallStats",
+        RetryMode::Read,
+        Some(&filter_dto),
+    )?;
+
+    let stats = response.overall_stats;
+    Ok(CampaignStats {
+        connections_sent: stats.connections_sent,
+        connections_accepted: stats.connections_accepted,
+        connection_acceptance_rate: stats.connection_acceptance_rate,
+        messages_sent: stats.messages_sent,
+        messages_started: stats.total_message_started,
+        message_replies: stats.total_message_replies,
+        message_reply_rate: stats.message_reply_rate,
+        inmail_messages_sent: stats.inmail_messages_sent,
+        inmails_started: stats.total_inmail_started,
+        inmail_replies: stats.total_inmail_replies,
+        inmail_reply_rate: stats.inmail_reply_rate,
+        profile_views: stats.profile_views,
+        post_likes: stats.post_likes,
+        follows: stats.follows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+    use std::rc::Rc;
+
+    fn client() -> (Rc<MemoryTransport>, HttpClient) {
+        let transport = Rc::new(MemoryTransport::new());
+        let http = HttpClient::for_tests(transport.clone());
+        (transport, http)
+    }
+
+    fn stats_filter() -> StatsFilter {
+        StatsFilter {
+            campaign_ids: vec![1],
+            account_ids: vec![],
+            start_date: "2024-01-01T00:00:00Z".to_string(),
+            end_date: "2024-01-08T00:00:00Z".to_string(),
+        }
+    }
+
+    #[test]
+    fn webhook_event_types_round_trip() {
+        let known = [
+            WebhookEventType::ConnectionRequestSent,
+            WebhookEventType::ConnectionAccepted,
+            WebhookEventType::MessageSent,
+            WebhookEventType::MessageReplied,
+            WebhookEventType::EveryMessageReplied,
+            WebhookEventType::InmailSent,
+            WebhookEventType::InmailReplied,
+            WebhookEventType::FollowSent,
+            WebhookEventType::PostLiked,
+            WebhookEventType::ProfileViewed,
+            WebhookEventType::CampaignCompleted,
+            WebhookEventType::LeadTagUpdated,
+        ];
+
+        for event_type in known {
+            let wire = webhook_event_type_to_string(&event_type);
+            assert!(wire.chars().all(|c| c.is_ascii_uppercase() || c == '_'));
+            assert_eq!(
+                webhook_event_type_to_string(&map_webhook_event_type(&wire)),
+                wire
+            );
+        }
+    }
+
+    #[test]
+    fn webhook_event_type_reading_accepts_other_spellings() {
+        assert!(matches!(
+            map_webhook_event_type("message_reply_received"),
+            WebhookEventType::MessageReplied
+        ));
+        assert!(matches!(
+            map_webhook_event_type("ConnectionAccepted"),
+            WebhookEventType::ConnectionAccepted
+        ));
+    }
+
+    #[test]
+    fn unknown_webhook_event_type_keeps_raw_value() {
+        let event_type = map_webhook_event_type("POST_COMMENTED");
+        assert!(matches!(&event_type, WebhookEventType::Other(raw) if raw == "POST_COMMENTED"));
+        assert_eq!(webhook_event_type_to_string(&event_type), "POST_COMMENTED");
+    }
+
+    fn webhook(id: u64, name: &str, url: &str) -> Webhook {
+        Webhook {
+            id,
+            webhook_name: name.to_string(),
+            webhook_url: url.to_string(),
+            event_type: WebhookEventType::MessageReplied,
+            campaign_ids: vec![1, 2],
+            is_active: true,
+        }
+    }
+
+    fn desired(name: &str, url: &str) -> CreateWebhookRequest {
+        CreateWebhookRequest {
+            webhook_name: name.to_string(),
+            webhook_url: url.to_string(),
+            event_type: WebhookEventType::MessageReplied,
+            campaign_ids: vec![2, 1],
+            is_active: true,
+        }
+    }
+
+    fn ensure_options(prefix: Option<&str>, prune: bool) -> WebhookEnsureOptions {
+        WebhookEnsureOptions {
+            dry_run: true,
+            name_prefix: prefix.map(str::to_string),
+            prune,
+        }
+    }
+
+    #[test]
+    fn webhook_plan_keeps_unlisted_webhooks_without_prune() {
+        let existing = vec![
+            webhook(1, "sync-replies", "https://a"),
+            webhook(2, "hand-made", "https://b"),
+            webhook(3, "sync-old", "https://c"),
+        ];
+        let wanted = vec![
+            desired("sync-replies", "https://a"),
+            desired("sync-new", "https://d"),
+        ];
+
+        let plan = plan_webhooks(wanted, existing, &ensure_options(None, false)).unwrap();
+        assert_eq!(plan.unchanged.len(), 1);
+        assert_eq!(plan.creates.len(), 1);
+        assert!(plan.deletes.is_empty());
+        let ignored: Vec<u64> = plan.ignored.iter().map(|w| w.id).collect();
+        assert_eq!(ignored, [2, 3]);
+    }
+
+    #[test]
+    fn webhook_plan_prunes_only_inside_prefix() {
+        let existing = vec![
+            webhook(1, "sync-replies", "https://a"),
+            webhook(2, "hand-made", "https://b"),
+            webhook(3, "sync-old", "https://c"),
+            webhook(4, "sync-replies", "https://dup"),
+        ];
+        let wanted = vec![desired("sync-replies", "https://new")];
+
+        let plan = plan_webhooks(wanted, existing, &ensure_options(Some("sync-"), true)).unwrap();
+        assert_eq!(plan.updates.len(), 1);
+        assert_eq!(plan.updates[0].current.id, 1);
+        assert_eq!(
+            plan.updates[0].changes.webhook_url.as_deref(),
+            Some("https://new")
+        );
+        assert!(plan.updates[0].changes.campaign_ids.is_none());
+        let deletes: Vec<u64> = plan.deletes.iter().map(|w| w.id).collect();
+        assert_eq!(deletes, [3, 4]);
+        let ignored: Vec<u64> = plan.ignored.iter().map(|w| w.id).collect();
+        assert_eq!(ignored, [2]);
+    }
+
+    #[test]
+    fn webhook_plan_rejects_names_outside_prefix_and_duplicates() {
+        let options = ensure_options(Some("sync-"), false);
+        let error =
+            plan_webhooks(vec![desired("other", "https://a")], vec![], &options).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+
+        let wanted = vec![
+            desired("sync-a", "https://a"),
+            desired("sync-a", "https://b"),
+        ];
+        let error = plan_webhooks(wanted, vec![], &options).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+    }
+
+    fn webhook_json(id: u64, url: &str) -> String {
+        format!(
+            r#"{{"id":{},"webhookName":"sync","webhookUrl":"{}","eventType":"MESSAGE_SENT","campaignIds":[],"isActive":true}}"#,
+            id, url
+        )
+    }
+
+    fn url_change(url: &str) -> UpdateWebhookRequest {
+        UpdateWebhookRequest {
+            webhook_name: None,
+            webhook_url: Some(url.to_string()),
+            event_type: None,
+            campaign_ids: None,
+            is_active: None,
+        }
+    }
+
+    #[test]
+    fn webhook_update_reads_back_the_stored_webhook() {
+        let (transport, http) = client();
+        transport.push_json(200, "");
+        transport.push_json(200, &webhook_json(7, "https://new"));
+
+        let webhook = webhooks_update(&http, 7, url_change("https://new"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(webhook.webhook_url, "https://new");
+        let requests = transport.requests();
+        assert!(matches!(requests[0].method, HttpMethod::Patch));
+        assert!(requests[1].path.contains("GetWebhookById?webhookId=7"));
+    }
+
+    #[test]
+    fn webhook_update_succeeds_when_read_back_fails() {
+        let (transport, http) = client();
+        transport.push_json(200, "");
+        transport.push_json(404, r#"{"detail":"gone"}"#);
+
+        let webhook = webhooks_update(&http, 7, url_change("https://new")).unwrap();
+        assert!(webhook.is_none());
+    }
+
+    #[test]
+    fn webhook_update_failure_is_an_error() {
+        let (transport, http) = client();
+        transport.push_json(400, r#"{"detail":"bad url"}"#);
+
+        let error = webhooks_update(&http, 7, url_change("nope")).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn stats_reads_overall_counters() {
+        let (transport, http) = client();
+        transport.push_json(
+            200,
+            r#"{"overallStats":{"connectionsSent":10,"connectionsAccepted":4,
+                "connectionAcceptanceRate":40.0,"messagesSent":7,"totalMessageStarted":5,
+                "totalMessageReplies":2,"messageReplyRate":28.57,"inmailMessagesSent":3,
+                "totalInmailStarted":2,"totalInmailReplies":1,"inMailReplyRate":50.0,
+                "profileViews":6,"postLikes":2,"follows":1}}"#,
+        );
+
+        let stats = stats_get_overall(&http, stats_filter()).unwrap();
+        assert_eq!(stats.connections_sent, 10);
+        assert_eq!(stats.connections_accepted, 4);
+        assert_eq!(stats.connection_acceptance_rate, 40.0);
+        assert_eq!(stats.messages_sent, 7);
+        assert_eq!(stats.messages_started, 5);
+        assert_eq!(stats.message_replies, 2);
+        assert_eq!(stats.message_reply_rate, 28.57);
+        assert_eq!(stats.inmail_messages_sent, 3);
+        assert_eq!(stats.inmails_started, 2);
+        assert_eq!(stats.inmail_replies, 1);
+        assert_eq!(stats.inmail_reply_rate, 50.0);
+        assert_eq!(stats.profile_views, 6);
+        assert_eq!(stats.post_likes, 2);
+        assert_eq!(stats.follows, 1);
+    }
+
+    #[test]
+    fn stats_rates_default_to_zero() {
+        let (transport, http) = client();
+        transport.push_json(
+            200,
+            r#"{"overallStats":{"connectionsSent":0,"connectionsAccepted":0,"messagesSent":0,
+                "totalMessageStarted":0,"totalMessageReplies":0,"inmailMessagesSent":0,
+                "totalInmailStarted":0,"totalInmailReplies":0,"profileViews":0,
+                "postLikes":0,"follows":0}}"#,
+        );
+
+        let stats = stats_get_overall(&http, stats_filter()).unwrap();
+        assert_eq!(stats.connection_acceptance_rate, 0.0);
+        assert_eq!(stats.message_reply_rate, 0.0);
+        assert_eq!(stats.inmail_reply_rate, 0.0);
+    }
+
+    #[test]
+    fn stats_without_overall_stats_is_a_decode_error() {
+        let (transport, http) = client();
+        transport.push_json(200, r#"{"somethingElse":{}}"#);
+
+        let error = stats_get_overall(&http, stats_filter()).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::Decode);
+    }
+
+    #[test]
+    fn stats_missing_counter_is_a_decode_error() {
+        let (transport, http) = client();
+        transport.push_json(200, r#"{"overallStats":{"connectionsSent":10}}"#);
+
+        let error = stats_get_overall(&http, stats_filter()).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::Decode);
+    }
+
+    fn chatroom_json(timestamps: &[&str]) -> String {
+        let messages: Vec<String> = timestamps
+            .iter()
+            .map(|t| format!(r#"{{"createdAt":"{}","body":"{}","sender":"ME"}}"#, t, t))
+            .collect();
+        format!(
+            r#"{{"id":"c1","linkedInAccountId":3,"messages":[{}]}}"#,
+            messages.join(",")
+        )
+    }
+
+    fn message_times(thread: &ConversationThread) -> Vec<&str> {
+        thread
+            .messages
+            .iter()
+            .map(|m| m.created_at.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn conversation_messages_sorted_by_instant() {
+        let (transport, http) = client();
+        transport.push_json(
+            200,
+            &chatroom_json(&[
+                "2024-05-08T10:48:02.500Z",
+                "2024-05-08T12:00:00+02:00",
+                "2024-05-08T10:48:02Z",
+            ]),
+        );
+
+        let thread = inbox_get_conversation(&http, 3, "c1".to_string()).unwrap();
+        assert_eq!(
+            message_times(&thread),
+            [
+                "2024-05-08T12:00:00+02:00",
+                "2024-05-08T10:48:02Z",
+                "2024-05-08T10:48:02.500Z",
+            ]
+        );
+    }
+
+    #[test]
+    fn conversation_keeps_api_order_with_unreadable_timestamp() {
+        let (transport, http) = client();
+        transport.push_json(
+            200,
+            &chatroom_json(&["2024-05-08T10:48:02Z", "yesterday", "2024-05-08T09:00:00Z"]),
+        );
+
+        let thread = inbox_get_conversation(&http, 3, "c1".to_string()).unwrap();
+        assert_eq!(
+            message_times(&thread),
+            ["2024-05-08T10:48:02Z", "yesterday", "2024-05-08T09:00:00Z"]
+        );
+    }
+
+    fn list_page_json(total_count: u32, ids: impl IntoIterator<Item = u64>) -> String {
+        let items: Vec<String> = ids
+            .into_iter()
+            .map(|id| {
+                format!(
+                    r#"{{"id":{},"name":"l{}","totalItemsCount":0,"listType":"USER_LIST","creationTime":"2024-01-01T00:00:00Z","campaignIds":[]}}"#,
+                    id, id
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"totalCount":{},"items":[{}]}}"#,
+            total_count,
+            items.join(",")
+        )
+    }
+
+    fn list_filter() -> ListGetAllFilter {
+        ListGetAllFilter {
+            offset: 0,
+            limit: 10,
+            keyword: None,
+        }
+    }
+
+    /// `(offset, limit)` sent in each request body.
+    fn requested_pages(transport: &MemoryTransport) -> Vec<(u64, u64)> {
+        transport
+            .requests()
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(request.body.as_deref().unwrap()).unwrap();
+                (
+                    body["offset"].as_u64().unwrap(),
+                    body["limit"].as_u64().unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn list_ids(lists: &[ListSummary]) -> Vec<u64> {
+        lists.iter().map(|l| l.id).collect()
+    }
+
+    #[test]
+    fn all_pages_dedupes_items_repeated_across_pages() {
+        let (transport, http) = client();
+        transport.push_json(200, &list_page_json(150, 1..=100));
+        transport.push_json(200, &list_page_json(150, [99, 100, 101]));
+        transport.push_json(200, &list_page_json(150, []));
+
+        let lists = lists_get_all_pages(&http, list_filter(), None).unwrap();
+        assert_eq!(list_ids(&lists), (1..=101).collect::<Vec<_>>());
+        assert_eq!(
+            requested_pages(&transport),
+            [(0, 100), (100, 100), (103, 100)]
+        );
+    }
+
+    #[test]
+    fn all_pages_stops_at_max_items() {
+        let (transport, http) = client();
+        transport.push_json(200, &list_page_json(10, [1, 2, 2]));
+        transport.push_json(200, &list_page_json(10, [3]));
+
+        let lists = lists_get_all_pages(&http, list_filter(), Some(3)).unwrap();
+        assert_eq!(list_ids(&lists), [1, 2, 3]);
+        assert_eq!(requested_pages(&transport), [(0, 3), (3, 1)]);
+    }
+
+    #[test]
+    fn all_pages_continues_after_short_page() {
+        let (transport, http) = client();
+        transport.push_json(200, &list_page_json(5, [1, 2]));
+        transport.push_json(200, &list_page_json(5, [3, 4, 5]));
+
+        let lists = lists_get_all_pages(&http, list_filter(), None).unwrap();
+        assert_eq!(list_ids(&lists), [1, 2, 3, 4, 5]);
+        assert_eq!(requested_pages(&transport), [(0, 100), (2, 100)]);
+    }
+
+    #[test]
+    fn all_pages_stops_on_empty_page_below_total_count() {
+        let (transport, http) = client();
+        transport.push_json(200, &list_page_json(300, 1..=100));
+        transport.push_json(200, &list_page_json(300, []));
+
+        let lists = lists_get_all_pages(&http, list_filter(), None).unwrap();
+        assert_eq!(lists.len(), 100);
+        assert_eq!(requested_pages(&transport), [(0, 100), (100, 100)]);
+    }
+
+    fn leads(count: usize) -> Vec<Lead> {
+        (0..count)
+            .map(|i| Lead {
+                first_name: format!("First{}", i),
+                last_name: format!("Last{}", i),
+                profile_url: format!("https://www.linkedin.com/in/lead-{}", i),
+                location: None,
+                summary: None,
+                company_name: None,
+                position: None,
+                about: None,
+                email_address: None,
+                custom_user_fields: vec![],
+            })
+            .collect()
+    }
+
+    fn add_leads_json(added: u32, updated: u32, failed: u32) -> String {
+        format!(
+            r#"{{"addedLeadsCount":{},"updatedLeadsCount":{},"failedLeadsCount":{}}}"#,
+            added, updated, failed
+        )
+    }
+
+    fn leads_per_request(transport: &MemoryTransport) -> Vec<usize> {
+        transport
+            .requests()
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(request.body.as_deref().unwrap()).unwrap();
+                body["leads"].as_array().unwrap().len()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn add_leads_counts_failed_chunk_as_failed() {
+        let (transport, http) = client();
+        transport.push_json(200, &add_leads_json(90, 10, 0));
+        transport.push_json(500, r#"{"detail":"boom"}"#);
+        transport.push_json(200, &add_leads_json(40, 0, 10));
+
+        let result = lists_add_leads_v2(&http, 5, leads(250)).unwrap();
+        assert_eq!(leads_per_request(&transport), [100, 100, 50]);
+        assert_eq!(result.added_leads_count, 130);
+        assert_eq!(result.updated_leads_count, 10);
+        assert_eq!(result.failed_leads_count, 110);
+
+        let starts: Vec<u32> = result.chunks.iter().map(|c| c.start).collect();
+        assert_eq!(starts, [0, 100, 200]);
+        let failed = &result.chunks[1];
+        assert_eq!((failed.lead_count, failed.failed_leads_count), (100, 100));
+        assert_eq!(
+            failed.error.as_ref().map(|e| e.code),
+            Some(ApiErrorCode::ServerError)
+        );
+        assert!(result.chunks[0].error.is_none() && result.chunks[2].error.is_none());
+    }
+
+    #[test]
+    fn add_leads_fails_only_when_every_chunk_fails() {
+        let (transport, http) = client();
+        transport.push_json(400, r#"{"detail":"bad lead"}"#);
+        transport.push_json(500, r#"{"detail":"boom"}"#);
+
+        let error = lists_add_leads_v2(&http, 5, leads(150)).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+        assert_eq!(leads_per_request(&transport), [100, 50]);
+    }
+
+    #[test]
+    fn add_leads_single_chunk_error_is_returned() {
+        let (transport, http) = client();
+        transport.push_json(400, r#"{"detail":"bad lead"}"#);
+
+        let error = lists_add_leads_v2(&http, 5, leads(3)).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn tag_changes_require_a_lead_identifier() {
+        let (transport, http) = client();
+
+        let error = lead_add_tags(
+            &http,
+            LeadAddTagsRequest {
+                lead_profile_url: None,
+                lead_linked_in_id: None,
+                tags: vec!["vip".to_string()],
+                create_tag_if_not_existing: true,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+
+        let error = lead_remove_tags(
+            &http,
+            LeadRemoveTagsRequest {
+                lead_profile_url: None,
+                lead_linked_in_id: None,
+                tags: vec!["vip".to_string()],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+        assert!(transport.requests().is_empty());
+    }
+
+    #[test]
+    fn tags_get_all_stops_at_max_items() {
+        let (transport, http) = client();
+        transport.push_json(
+            200,
+            r#"{"totalCount":500,"items":[{"name":"a"},{"name":"b"}]}"#,
+        );
+
+        let tags = tags_get_all(&http, Some(2)).unwrap();
+        assert_eq!(tags, ["a", "b"]);
+        assert_eq!(requested_pages(&transport), [(0, 2)]);
+    }
+
+    /// A `MESSAGE_REPLY_RECEIVED` delivery in HeyReach's snake_case shape.
+    const MESSAGE_REPLY_EVENT: &str = r#"{
+        "is_inmail": false,
+        "recent_messages": [
+            {
+                "creation_time": "2024-05-08T10:40:11.123Z",
+                "message": "Hi Jane, thanks for connecting!",
+                "is_reply": false
+            },
+            {
+                "creation_time": "2024-05-08T10:48:02Z",
+                "message": "Happy to chat next week.",
+                "is_reply": true
+            }
+        ],
+        "conversation_id": "2-ZTQ4NjYxN2EtMjZlNi00",
+        "campaign": { "name": "Q2 founders", "id": 81234, "status": "IN_PROGRESS" },
+        "sender": {
+            "id": 30511,
+            "first_name": "Sam",
+            "last_name": "Seller",
+            "full_name": "Sam Seller",
+            "email_address": "sam@example.com",
+            "profile_url": "https://www.linkedin.com/in/sam-seller"
+        },
+        "lead": {
+            "id": "ACoAAB1xYz",
+            "profile_url": "https://www.linkedin.com/in/jane-doe",
+            "first_name": "Jane",
+            "last_name": "Doe",
+            "full_name": "Jane Doe",
+            "company_name": "Acme",
+            "position": "CTO",
+            "location": "Berlin",
+            "email_address": null,
+            "tags": ["warm", "founder"]
+        },
+        "timestamp": "2024-05-08T10:48:02.512Z",
+        "event_type": "message_reply_received"
+    }"#;
+
+    /// A `CAMPAIGN_COMPLETED` delivery: no lead, sender or messages.
+    const CAMPAIGN_COMPLETED_EVENT: &str = r#"{
+        "campaign": { "name": "Q2 founders", "id": 81234, "status": "FINISHED" },
+        "timestamp": "2024-05-09T08:00:00Z",
+        "event_type": "CAMPAIGN_COMPLETED",
+        "id": 987654321
+    }"#;
+
+    #[test]
+    fn parses_message_reply_event() {
+        let event = webhooks_parse_event(MESSAGE_REPLY_EVENT).unwrap();
+
+        assert!(matches!(event.event_type, WebhookEventType::MessageReplied));
+        assert_eq!(event.event_id, None);
+        assert_eq!(event.timestamp, "2024-05-08T10:48:02.512Z");
+        assert_eq!(
+            event.conversation_id.as_deref(),
+            Some("2-ZTQ4NjYxN2EtMjZlNi00")
+        );
+        assert!(!event.is_inmail);
+
+        let lead = event.lead.unwrap();
+        assert_eq!(lead.id.as_deref(), Some("ACoAAB1xYz"));
+        assert_eq!(lead.full_name.as_deref(), Some("Jane Doe"));
+        assert_eq!(lead.email_address, None);
+        assert_eq!(lead.tags, ["warm", "founder"]);
+
+        let sender = event.sender.unwrap();
+        assert_eq!(sender.id, Some(30511));
+        assert_eq!(
+            sender.profile_url.as_deref(),
+            Some("https://www.linkedin.com/in/sam-seller")
+        );
+
+        let campaign = event.campaign.unwrap();
+        assert_eq!(campaign.id, Some(81234));
+        assert_eq!(campaign.status.as_deref(), Some("IN_PROGRESS"));
+
+        assert_eq!(event.messages.len(), 2);
+        assert!(!event.messages[0].is_reply);
+        assert!(event.messages[1].is_reply);
+        assert_eq!(event.messages[1].content, "Happy to chat next week.");
+        assert_eq!(
+            event.messages[1].sent_at.as_deref(),
+            Some("2024-05-08T10:48:02Z")
+        );
+    }
+
+    #[test]
+    fn parses_event_without_lead_and_numeric_id() {
+        let event = webhooks_parse_event(CAMPAIGN_COMPLETED_EVENT).unwrap();
+
+        assert!(matches!(
+            event.event_type,
+            WebhookEventType::CampaignCompleted
+        ));
+        assert_eq!(event.event_id.as_deref(), Some("987654321"));
+        assert!(event.lead.is_none() && event.sender.is_none());
+        assert!(event.messages.is_empty());
+    }
+
+    #[test]
+    fn parses_camel_case_event_with_string_id() {
+        let event = webhooks_parse_event(
+            r#"{"eventId":"evt-1","eventType":"LEAD_TAG_UPDATED",
+                "timestamp":"2024-05-08T10:48:02Z","lead":{"id":12,"profileUrl":"u"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.event_id.as_deref(), Some("evt-1"));
+        assert!(matches!(event.event_type, WebhookEventType::LeadTagUpdated));
+        assert_eq!(event.lead.unwrap().id.as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn malformed_event_is_a_decode_error() {
+        for body in [
+            "",
+            "{",
+            r#"{"timestamp":"2024-05-08T10:48:02Z"}"#,
+            r#"{"event_type":"MESSAGE_SENT"}"#,
+            r#"{"event_type":"","timestamp":"2024-05-08T10:48:02Z"}"#,
+            r#"{"event_type":"MESSAGE_SENT","timestamp":"t","sender":{"id":"abc"}}"#,
+        ] {
+            let error = webhooks_parse_event(body).unwrap_err();
+            assert_eq!(error.code, ApiErrorCode::Decode, "{}", body);
+        }
+    }
+
+    fn request_json(transport: &MemoryTransport, index: usize) -> serde_json::Value {
+        serde_json::from_slice(transport.requests()[index].body.as_deref().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn create_empty_list_sends_type() {
+        let (transport, http) = client();
+        transport.push_json(
+            200,
+            r#"{"id":11,"name":"Leads","listType":"USER_LIST","creationTime":"2024-05-08T10:48:02Z"}"#,
+        );
+        transport.push_json(
+            200,
+            r#"{"id":12,"name":"Companies","totalItemsCount":0,"listType":"COMPANY_LIST",
+                "creationTime":"2024-05-08T10:48:02Z","campaignIds":[]}"#,
+        );
+
+        let leads = lists_create_empty(
+            &http,
+            CreateListRequest {
+                name: "Leads".to_string(),
+                list_type: ListType::Leads,
+            },
+        )
+        .unwrap();
+        let companies = lists_create_empty(
+            &http,
+            CreateListRequest {
+                name: "Companies".to_string(),
+                list_type: ListType::Companies,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            request_json(&transport, 0),
+            serde_json::json!({"name": "Leads", "type": "USER_LIST"})
+        );
+        assert_eq!(
+            request_json(&transport, 1),
+            serde_json::json!({"name": "Companies", "type": "COMPANY_LIST"})
+        );
+        assert_eq!((leads.id, leads.total_items_count), (11, 0));
+        assert!(matches!(leads.list_type, ListType::Leads));
+        assert!(leads.campaign_ids.is_empty());
+        assert!(matches!(companies.list_type, ListType::Companies));
+    }
+
+    #[test]
+    fn create_empty_list_rejects_unknown_type() {
+        let (transport, http) = client();
+
+        let error = lists_create_empty(
+            &http,
+            CreateListRequest {
+                name: "x".to_string(),
+                list_type: ListType::Unknown,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+        assert!(transport.requests().is_empty());
+    }
+
+    #[test]
+    fn list_summary_fields_are_required() {
+        let (transport, http) = client();
+        transport.push_json(
+            200,
+            r#"{"totalCount":1,"items":[{"id":1,"name":"l","listType":"USER_LIST",
+                "creationTime":"2024-01-01T00:00:00Z"}]}"#,
+        );
+
+        let error = lists_get_all(&http, list_filter()).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::Decode);
+    }
+
+    #[test]
+    fn list_companies_reads_linked_in_url() {
+        let (transport, http) = client();
+        transport.push_json(
+            200,
+            r#"{"totalCount":2,"items":[
+                {"name":"Acme","linkedInUrl":"https://www.linkedin.com/company/acme",
+                 "industry":"Software","companySize":"51-200","location":"Berlin"},
+                {"name":"Initech","profileUrl":"https://www.linkedin.com/company/initech"}]}"#,
+        );
+
+        let page = lists_get_companies(&http, 9, 0, 10, None).unwrap();
+        assert_eq!(page.total_count, 2);
+        assert_eq!(
+            page.items[0].linked_in_url.as_deref(),
+            Some("https://www.linkedin.com/company/acme")
+        );
+        assert_eq!(page.items[0].company_size.as_deref(), Some("51-200"));
+        assert_eq!(
+            page.items[1].linked_in_url.as_deref(),
+            Some("https://www.linkedin.com/company/initech")
+        );
+        assert_eq!(page.items[1].industry, None);
+        assert_eq!(
+            request_json(&transport, 0),
+            serde_json::json!({"listId": 9, "offset": 0, "limit": 10})
+        );
+    }
+
+    fn stop_lead(url: Option<&str>, linked_in_id: Option<&str>) -> StopLeadRequest {
+        StopLeadRequest {
+            campaign_id: 81,
+            lead_profile_url: url.map(str::to_string),
+            lead_linked_in_id: linked_in_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn stop_lead_requires_a_lead_identifier() {
+        let (transport, http) = client();
+
+        let error = campaigns_stop_lead(&http, stop_lead(None, None)).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+        assert!(transport.requests().is_empty());
+    }
+
+    #[test]
+    fn stop_lead_sends_lead_url_and_linked_in_id() {
+        let (transport, http) = client();
+        transport.push_json(200, "");
+        transport.push_json(200, "");
+
+        campaigns_stop_lead(
+            &http,
+            stop_lead(Some("https://www.linkedin.com/in/jane"), None),
+        )
+        .unwrap();
+        campaigns_stop_lead(&http, stop_lead(None, Some("ACoAAB1xYz"))).unwrap();
+
+        let requests = transport.requests();
+        assert!(requests[0].path.ends_with("/campaign/StopLeadInCampaign"));
+        assert_eq!(
+            request_json(&transport, 0),
+            serde_json::json!({"campaignId": 81, "leadUrl": "https://www.linkedin.com/in/jane"})
+        );
+        assert_eq!(
+            request_json(&transport, 1),
+            serde_json::json!({"campaignId": 81, "leadLinkedInId": "ACoAAB1xYz"})
+        );
+    }
+
+    #[test]
+    fn lead_campaigns_sends_lookup_and_reads_statuses() {
+        let (transport, http) = client();
+        transport.push_json(
+            200,
+            r#"{"totalCount":2,"items":[
+                {"campaignId":81,"campaignName":"Q2 founders","campaignStatus":"ACTIVE",
+                 "leadCampaignStatus":"IN_PROGRESS"},
+                {"campaignId":82,"campaignName":"Old","campaignStatus":"FINISHED",
+                 "leadCampaignStatus":"MANUALLY_STOPPED"}]}"#,
+        );
+
+        let page = lead_get_campaigns(
+            &http,
+            LeadCampaignsRequest {
+                email: None,
+                linkedin_id: None,
+                profile_url: Some("https://www.linkedin.com/in/jane".to_string()),
+                offset: 0,
+                limit: 10,
+            },
+        )
+        .unwrap();
+
+        assert!(transport.requests()[0]
+            .path
+            .ends_with("/lead/GetCampaignsForLead"));
+        assert_eq!(
+            request_json(&transport, 0),
+            serde_json::json!({
+                "profileUrl": "https://www.linkedin.com/in/jane",
+                "offset": 0,
+                "limit": 10
+            })
+        );
+        assert_eq!(page.total_count, 2);
+        assert_eq!(page.items[0].campaign_id, 81);
+        assert_eq!(page.items[0].campaign_name, "Q2 founders");
+        assert!(matches!(
+            page.items[0].campaign_status,
+            CampaignStatus::Active
+        ));
+        assert!(matches!(
+            page.items[0].lead_status,
+            LeadCampaignStatus::InProgress
+        ));
+        assert!(matches!(
+            page.items[1].campaign_status,
+            CampaignStatus::Finished
+        ));
+        assert!(matches!(
+            page.items[1].lead_status,
+            LeadCampaignStatus::ManuallyStopped
+        ));
+    }
+
+    #[test]
+    fn lead_campaigns_missing_name_is_a_decode_error() {
+        let (transport, http) = client();
+        transport.push_json(200, r#"{"totalCount":1,"items":[{"campaignId":81}]}"#);
+
+        let error = lead_get_campaigns(
+            &http,
+            LeadCampaignsRequest {
+                email: Some("jane@example.com".to_string()),
+                linkedin_id: None,
+                profile_url: None,
+                offset: 0,
+                limit: 10,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::Decode);
+        assert_eq!(
+            request_json(&transport, 0),
+            serde_json::json!({"email": "jane@example.com", "offset": 0, "limit": 10})
+        );
+    }
+
+    fn network_page_json(total_count: u32, ids: std::ops::Range<u32>) -> String {
+        let items: Vec<String> = ids
+            .map(|id| format!(r#"{{"profileUrl":"https://www.linkedin.com/in/c{}"}}"#, id))
+            .collect();
+        format!(
+            r#"{{"totalCount":{},"items":[{}]}}"#,
+            total_count,
+            items.join(",")
+        )
+    }
+
+    #[test]
+    fn network_pages_request_full_pages_by_number() {
+        let (transport, http) = client();
+        transport.push_json(200, &network_page_json(250, 0..100));
+        transport.push_json(200, &network_page_json(250, 100..200));
+
+        let connections = li_account_get_network_all_pages(&http, 7, Some(150)).unwrap();
+
+        assert_eq!(connections.len(), 150);
+        assert_eq!(
+            connections[149].profile_url,
+            "https://www.linkedin.com/in/c149"
+        );
+        assert_eq!(transport.requests().len(), 2);
+        assert_eq!(
+            request_json(&transport, 0),
+            serde_json::json!({"senderId": 7, "pageNumber": 0, "pageSize": 100})
+        );
+        assert_eq!(
+            request_json(&transport, 1),
+            serde_json::json!({"senderId": 7, "pageNumber": 1, "pageSize": 100})
+        );
+    }
+
+    #[test]
+    fn campaign_leads_decode_profiles_senders_and_statuses() {
+        let (transport, http) = client();
+        let statuses = [
+            "PENDING",
+            "IN_PROGRESS",
+            "FINISHED",
+            "FAILED",
+            "EXCLUDED",
+            "MANUALLY_STOPPED",
+            "PAUSED",
+        ];
+        let mut items: Vec<String> = statuses
+            .iter()
+            .enumerate()
+            .map(|(i, status)| {
+                format!(
+                    r#"{{"linkedInUserProfile":{{"firstName":"F{i}","lastName":"L{i}",
+                        "profileUrl":"https://www.linkedin.com/in/l{i}"}},
+                        "leadCampaignStatus":"{status}","linkedInSenderId":{i}}}"#
+                )
+            })
+            .collect();
+        // Older responses name the profile `lead` and may omit the status and
+        // sender.
+        items.push(
+            r#"{"lead":{"firstName":"Old","lastName":"Shape",
+                "profileUrl":"https://www.linkedin.com/in/old"}}"#
+                .to_string(),
+        );
+        transport.push_json(
+            200,
+            &format!(r#"{{"totalCount":8,"items":[{}]}}"#, items.join(",")),
+        );
+
+        let page = campaigns_get_leads(
+            &http,
+            CampaignLeadsRequest {
+                campaign_id: 81,
+                offset: 0,
+                limit: 100,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(page.total_count, 8);
+        assert_eq!(
+            page.items.iter().map(|l| l.status).collect::<Vec<_>>(),
+            vec![
+                LeadCampaignStatus::Pending,
+                LeadCampaignStatus::InProgress,
+                LeadCampaignStatus::Finished,
+                LeadCampaignStatus::Failed,
+                LeadCampaignStatus::Excluded,
+                LeadCampaignStatus::ManuallyStopped,
+                LeadCampaignStatus::Unknown,
+                LeadCampaignStatus::Unknown,
+            ]
+        );
+        assert_eq!(page.items[1].lead.first_name, "F1");
+        assert_eq!(
+            page.items[5].lead.profile_url,
+            "https://www.linkedin.com/in/l5"
+        );
+        assert_eq!(page.items[5].linked_in_account_id, Some(5));
+        assert_eq!(
+            page.items[7].lead.profile_url,
+            "https://www.linkedin.com/in/old"
+        );
+        assert_eq!(page.items[7].linked_in_account_id, None);
+    }
+}
@@ -0,0 +1,167 @@
+//! Clock access for the request pipeline.
+//!
+//! Uses `wasi:clocks` inside the component and `std::time` on native targets,
+//! so the pipeline can run under `cargo test` without a WASI runtime.
+
+use crate::wasi::clocks::monotonic_clock;
+#[cfg(target_arch = "wasm32")]
+use crate::wasi::clocks::wall_clock;
+use crate::wasi::io::poll::{self, Pollable};
+
+/// Monotonic time in nanoseconds, for measuring intervals.
+#[cfg(target_arch = "wasm32")]
+pub fn now_ns() -> u64 {
+    monotonic_clock::now()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_ns() -> u64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_nanos() as u64
+}
+
+/// Wall-clock time in milliseconds since the Unix epoch.
+#[cfg(target_arch = "wasm32")]
+pub fn unix_now_ms() -> u64 {
+    let now = wall_clock::now();
+    now.seconds * 1000 + (now.nanoseconds / 1_000_000) as u64
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn unix_now_ms() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Parses an RFC 3339 timestamp such as `2024-05-08T10:48:02.123Z` into
+/// milliseconds since the Unix epoch. A missing offset is taken as UTC.
+pub fn parse_timestamp_ms(timestamp: &str) -> Option<u64> {
+    let timestamp = timestamp.trim();
+    let (date, time) = timestamp.split_once(['T', 't', ' '])?;
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: i64 = date_parts.next()?.parse().ok()?;
+    let day: i64 = date_parts.next()?.parse().ok()?;
+    // RFC 3339 years have four digits; the bound also keeps the arithmetic
+    // below from overflowing.
+    if !(0..=9999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (time, offset_secs) = if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        (time, 0)
+    } else if let Some(idx) = time.rfind(['+', '-']) {
+        let (time, offset) = time.split_at(idx);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (hours, minutes) = offset[1..].split_once(':')?;
+        let hours: i64 = hours.parse().ok()?;
+        let minutes: i64 = minutes.parse().ok()?;
+        if !(0..=23).contains(&hours) || !(0..=59).contains(&minutes) {
+            return None;
+        }
+        (time, sign * (hours * 3600 + minutes * 60))
+    } else {
+        (time, 0)
+    };
+
+    let (hms, fraction) = match time.split_once('.') {
+        Some((hms, fraction)) => (hms, fraction),
+        None => (time, ""),
+    };
+    let mut hms_parts = hms.splitn(3, ':');
+    let hour: i64 = hms_parts.next()?.parse().ok()?;
+    let minute: i64 = hms_parts.next()?.parse().ok()?;
+    let second: i64 = hms_parts.next()?.parse().ok()?;
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let millis: i64 = if fraction.is_empty() {
+        0
+    } else {
+        if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        format!("{:0<3}", &fraction[..fraction.len().min(3)])
+            .parse()
+            .ok()?
+    };
+
+    let days = days_from_civil(year, month, day);
+    let secs = days * 86_400 + hour * 3600 + minute * 60 + second - offset_secs;
+    u64::try_from(secs * 1000 + millis).ok()
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Blocks the component on a monotonic-clock pollable.
+#[cfg(target_arch = "wasm32")]
+pub fn sleep_ms(ms: u64) {
+    if ms > 0 {
+        monotonic_clock::subscribe_duration(ms.saturating_mul(1_000_000)).block();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn sleep_ms(ms: u64) {
+    std::thread::sleep(std::time::Duration::from_millis(ms));
+}
+
+/// Blocks until `pollable` is ready or `timeout_ms` elapses. Returns `false`
+/// on timeout.
+pub fn wait_ready(pollable: &Pollable, timeout_ms: u64) -> bool {
+    let timer = monotonic_clock::subscribe_duration(timeout_ms.saturating_mul(1_000_000));
+    poll::poll(&[pollable, &timer]).contains(&0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fractions_and_offsets() {
+        assert_eq!(parse_timestamp_ms("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(
+            parse_timestamp_ms("2024-05-08T10:48:02.5Z"),
+            Some(1_715_165_282_500)
+        );
+        assert_eq!(
+            parse_timestamp_ms("2024-05-08T12:48:02+02:00"),
+            parse_timestamp_ms("2024-05-08T10:48:02Z")
+        );
+        assert_eq!(
+            parse_timestamp_ms("2024-05-08T10:48:02"),
+            parse_timestamp_ms("2024-05-08T10:48:02Z")
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        assert_eq!(parse_timestamp_ms("99999999999999-01-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp_ms("10000-01-01T00:00:00Z"), None);
+        assert_eq!(parse_timestamp_ms("1969-12-31T23:59:59Z"), None);
+        assert_eq!(
+            parse_timestamp_ms("2024-05-08T10:48:02+99999999999999:00"),
+            None
+        );
+        assert_eq!(parse_timestamp_ms("2024-13-08T10:48:02Z"), None);
+        assert_eq!(parse_timestamp_ms("not a timestamp"), None);
+    }
+}
@@ -0,0 +1,344 @@
+use crate::exports::heyreach::client::api::{
+    ApiError, ApiErrorCode, ClientConfig, LogLevel, RateLimit, RetryPolicy, Timeouts, UrlScheme,
+};
+use crate::http::api_error;
+use crate::{ratelimit, retry};
+use std::cell::RefCell;
+
+const DEFAULT_AUTHORITY: &str = "api.heyreach.io";
+const DEFAULT_CONNECT_TIMEOUT_MS: u32 = 10_000;
+const DEFAULT_FIRST_BYTE_TIMEOUT_MS: u32 = 30_000;
+const DEFAULT_BETWEEN_BYTES_TIMEOUT_MS: u32 = 30_000;
+
+/// Resolved destination and request behaviour for API requests.
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    pub scheme: UrlScheme,
+    pub authority: String,
+    pub path_prefix: String,
+    pub retry: RetryPolicy,
+    pub rate_limit: RateLimit,
+    /// Always fully populated; see `merge_timeouts`.
+    pub timeouts: Timeouts,
+    pub log_level: LogLevel,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            scheme: UrlScheme::Https,
+            authority: DEFAULT_AUTHORITY.to_string(),
+            path_prefix: String::new(),
+            retry: retry::default_policy(),
+            rate_limit: ratelimit::default_rate_limit(),
+            timeouts: Timeouts {
+                connect_ms: Some(DEFAULT_CONNECT_TIMEOUT_MS),
+                first_byte_ms: Some(DEFAULT_FIRST_BYTE_TIMEOUT_MS),
+                between_bytes_ms: Some(DEFAULT_BETWEEN_BYTES_TIMEOUT_MS),
+            },
+            log_level: LogLevel::Info,
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<HttpConfig> = RefCell::new(HttpConfig::default());
+}
+
+/// Returns the configuration used by the free functions of the `api` interface.
+pub fn current() -> HttpConfig {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+pub fn configure(config: ClientConfig) -> Result<(), ApiError> {
+    let resolved = resolve(config)?;
+    CURRENT.with(|c| *c.borrow_mut() = resolved);
+    Ok(())
+}
+
+pub fn resolve(config: ClientConfig) -> Result<HttpConfig, ApiError> {
+    let mut resolved = match config.base_url {
+        Some(base_url) => parse_base_url(&base_url)?,
+        None => HttpConfig::default(),
+    };
+
+    if let Some(scheme) = config.scheme {
+        resolved.scheme = scheme;
+    }
+
+    if let Some(authority) = config.authority {
+        resolved.authority = validate_authority(&authority)?;
+    }
+
+    if let Some(path_prefix) = config.path_prefix {
+        resolved.path_prefix = normalize_path_prefix(&path_prefix)?;
+    }
+
+    if let Some(retry) = config.retry {
+        resolved.retry = retry;
+    }
+
+    if let Some(rate_limit) = config.rate_limit {
+        resolved.rate_limit = rate_limit;
+    }
+
+    if let Some(timeouts) = config.timeouts {
+        resolved.timeouts = merge_timeouts(&resolved.timeouts, timeouts)?;
+    }
+
+    if let Some(log_level) = config.log_level {
+        resolved.log_level = log_level;
+    }
+
+    Ok(resolved)
+}
+
+/// Applies the fields set in `overrides` on top of `base`.
+///
+/// A timeout of 0 is rejected: it would make every request time out at once,
+/// and there is deliberately no way to disable a timeout.
+pub fn merge_timeouts(base: &Timeouts, overrides: Timeouts) -> Result<Timeouts, ApiError> {
+    for (name, value) in [
+        ("connect-ms", overrides.connect_ms),
+        ("first-byte-ms", overrides.first_byte_ms),
+        ("between-bytes-ms", overrides.between_bytes_ms),
+    ] {
+        if value == Some(0) {
+            return Err(api_error(
+                ApiErrorCode::BadRequest,
+                &format!("Invalid timeout {}: must be greater than 0", name),
+            ));
+        }
+    }
+
+    Ok(Timeouts {
+        connect_ms: overrides.connect_ms.or(base.connect_ms),
+        first_byte_ms: overrides.first_byte_ms.or(base.first_byte_ms),
+        between_bytes_ms: overrides.between_bytes_ms.or(base.between_bytes_ms),
+    })
+}
+
+fn parse_base_url(base_url: &str) -> Result<HttpConfig, ApiError> {
+    let base_url = base_url.trim();
+
+    let (scheme, rest) = if let Some(rest) = base_url.strip_prefix("https://") {
+        (UrlScheme::Https, rest)
+    } else if let Some(rest) = base_url.strip_prefix("http://") {
+        (UrlScheme::Http, rest)
+    } else {
+        return Err(api_error(
+            ApiErrorCode::BadRequest,
+            &format!(
+                "Invalid base URL '{}': expected http:// or https://",
+                base_url
+            ),
+        ));
+    };
+
+    if rest.contains('?') || rest.contains('#') {
+        return Err(api_error(
+            ApiErrorCode::BadRequest,
+            &format!(
+                "Invalid base URL '{}': query and fragment are not allowed",
+                base_url
+            ),
+        ));
+    }
+
+    let (authority, path) = match rest.find('/') {
+        Some(idx) => rest.split_at(idx),
+        None => (rest, ""),
+    };
+
+    Ok(HttpConfig {
+        scheme,
+        authority: validate_authority(authority)?,
+        path_prefix: normalize_path_prefix(path)?,
+        ..HttpConfig::default()
+    })
+}
+
+fn validate_authority(authority: &str) -> Result<String, ApiError> {
+    let authority = authority.trim();
+
+    if authority.is_empty() || authority.contains(['/', '?', '#', ' ']) {
+        return Err(api_error(
+            ApiErrorCode::BadRequest,
+            &format!("Invalid authority '{}'", authority),
+        ));
+    }
+
+    Ok(authority.to_string())
+}
+
+/// Ensures a leading `/` and strips trailing ones, so `"heyreach/"` becomes
+/// `"/heyreach"` and `"/"` becomes `""`.
+fn normalize_path_prefix(path_prefix: &str) -> Result<String, ApiError> {
+    let trimmed = path_prefix.trim().trim_matches('/');
+
+    if trimmed.contains(['?', '#', ' ']) {
+        return Err(api_error(
+            ApiErrorCode::BadRequest,
+            &format!("Invalid path prefix '{}'", path_prefix),
+        ));
+    }
+
+    if trimmed.is_empty() {
+        Ok(String::new())
+    } else {
+        Ok(format!("/{}", trimmed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_config() -> ClientConfig {
+        ClientConfig {
+            base_url: None,
+            scheme: None,
+            authority: None,
+            path_prefix: None,
+            retry: None,
+            rate_limit: None,
+            timeouts: None,
+            log_level: None,
+        }
+    }
+
+    fn config_with_base_url(base_url: &str) -> ClientConfig {
+        ClientConfig {
+            base_url: Some(base_url.to_string()),
+            ..empty_config()
+        }
+    }
+
+    fn config_with_timeouts(timeouts: Timeouts) -> ClientConfig {
+        ClientConfig {
+            timeouts: Some(timeouts),
+            ..empty_config()
+        }
+    }
+
+    #[test]
+    fn defaults_to_heyreach_api() {
+        let resolved = resolve(empty_config()).unwrap();
+        assert!(matches!(resolved.scheme, UrlScheme::Https));
+        assert_eq!(resolved.authority, "api.heyreach.io");
+        assert_eq!(resolved.path_prefix, "");
+    }
+
+    #[test]
+    fn base_url_with_port_and_prefix() {
+        let resolved = resolve(config_with_base_url(" http://host:8080/prefix/ ")).unwrap();
+        assert!(matches!(resolved.scheme, UrlScheme::Http));
+        assert_eq!(resolved.authority, "host:8080");
+        assert_eq!(resolved.path_prefix, "/prefix");
+
+        let resolved = resolve(config_with_base_url("https://proxy.internal/a/b")).unwrap();
+        assert_eq!(resolved.path_prefix, "/a/b");
+    }
+
+    #[test]
+    fn base_url_without_path() {
+        for base_url in [
+            "https://staging.example.com",
+            "https://staging.example.com/",
+        ] {
+            let resolved = resolve(config_with_base_url(base_url)).unwrap();
+            assert!(matches!(resolved.scheme, UrlScheme::Https));
+            assert_eq!(resolved.authority, "staging.example.com");
+            assert_eq!(resolved.path_prefix, "");
+        }
+    }
+
+    #[test]
+    fn invalid_base_urls_are_rejected() {
+        for base_url in [
+            "ftp://host/prefix",
+            "host:8080/prefix",
+            "HTTPS://host",
+            "https://host/prefix?x=1",
+            "https://host/prefix#frag",
+            "https://host?x=1",
+            "https://",
+            "https:///prefix",
+            "https://bad host/prefix",
+            "https://host/with space",
+        ] {
+            let error = resolve(config_with_base_url(base_url)).unwrap_err();
+            assert_eq!(error.code, ApiErrorCode::BadRequest, "{}", base_url);
+        }
+    }
+
+    #[test]
+    fn explicit_fields_override_base_url() {
+        let resolved = resolve(ClientConfig {
+            scheme: Some(UrlScheme::Https),
+            authority: Some("override:9443".to_string()),
+            path_prefix: Some("heyreach/".to_string()),
+            ..config_with_base_url("http://host:8080/prefix")
+        })
+        .unwrap();
+        assert!(matches!(resolved.scheme, UrlScheme::Https));
+        assert_eq!(resolved.authority, "override:9443");
+        assert_eq!(resolved.path_prefix, "/heyreach");
+
+        let resolved = resolve(ClientConfig {
+            path_prefix: Some("/".to_string()),
+            ..config_with_base_url("http://host:8080/prefix")
+        })
+        .unwrap();
+        assert_eq!(resolved.authority, "host:8080");
+        assert_eq!(resolved.path_prefix, "");
+    }
+
+    #[test]
+    fn invalid_overrides_are_rejected() {
+        for config in [
+            ClientConfig {
+                authority: Some("host/path".to_string()),
+                ..empty_config()
+            },
+            ClientConfig {
+                authority: Some(" ".to_string()),
+                ..empty_config()
+            },
+            ClientConfig {
+                path_prefix: Some("/a?b".to_string()),
+                ..empty_config()
+            },
+        ] {
+            let error = resolve(config).unwrap_err();
+            assert_eq!(error.code, ApiErrorCode::BadRequest);
+        }
+    }
+
+    #[test]
+    fn zero_timeout_is_rejected() {
+        let error = resolve(config_with_timeouts(Timeouts {
+            connect_ms: None,
+            first_byte_ms: Some(0),
+            between_bytes_ms: None,
+        }))
+        .unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+        assert!(error.message.contains("first-byte-ms"));
+    }
+
+    #[test]
+    fn unset_timeouts_keep_defaults() {
+        let resolved = resolve(config_with_timeouts(Timeouts {
+            connect_ms: Some(2_000),
+            first_byte_ms: None,
+            between_bytes_ms: None,
+        }))
+        .unwrap();
+        assert_eq!(resolved.timeouts.connect_ms, Some(2_000));
+        assert_eq!(
+            resolved.timeouts.first_byte_ms,
+            Some(DEFAULT_FIRST_BYTE_TIMEOUT_MS)
+        );
+    }
+}
@@ -0,0 +1,454 @@
+//! Page-at-a-time cursors over large result sets.
+//!
+//! Unlike the `...-all-pages` calls, a cursor only holds its position, so
+//! memory use stays at one page however large the result set is.
+
+use crate::client::{self, MAX_PAGE_SIZE};
+use crate::exports::heyreach::client::api::*;
+use crate::http::{api_error, HttpClient};
+use std::cell::Cell;
+
+/// Position and progress shared by every cursor type.
+struct Position {
+    /// Names the result set in position tokens so a token cannot be resumed
+    /// on the wrong kind of cursor.
+    kind: &'static str,
+    /// Identifies the list or filter, so a token from one result set is not
+    /// resumed against another of the same kind.
+    scope: String,
+    offset: Cell<u32>,
+    page_size: u32,
+    total_count: Cell<Option<u32>>,
+    done: Cell<bool>,
+}
+
+impl Position {
+    fn new(
+        kind: &'static str,
+        scope: String,
+        start: u32,
+        page_size: u32,
+        token: Option<String>,
+    ) -> Result<Self, ApiError> {
+        let offset = match token {
+            Some(token) => parse_token(kind, &scope, &token)?,
+            None => start,
+        };
+
+        Ok(Position {
+            kind,
+            scope,
+            offset: Cell::new(offset),
+            // 0 means "no preference" rather than one item per request.
+            page_size: if page_size == 0 {
+                MAX_PAGE_SIZE
+            } else {
+                page_size.min(MAX_PAGE_SIZE)
+            },
+            total_count: Cell::new(None),
+            done: Cell::new(false),
+        })
+    }
+
+    /// Fetches the page at the current offset and advances past it. A failed
+    /// fetch leaves the position unchanged, so the call can be repeated.
+    ///
+    /// Done once the offset reaches the total count or a page comes back
+    /// empty; a short page is not the end, as in `client::collect_pages`.
+    fn next<T>(
+        &self,
+        fetch: impl FnOnce(u32, u32) -> Result<(u32, Vec<T>), ApiError>,
+    ) -> Result<Option<Vec<T>>, ApiError> {
+        if self.done.get() {
+            return Ok(None);
+        }
+
+        let (total_count, page) = fetch(self.offset.get(), self.page_size)?;
+        let offset = self.offset.get().saturating_add(page.len() as u32);
+
+        self.offset.set(offset);
+        self.total_count.set(Some(total_count));
+        if page.is_empty() || offset >= total_count {
+            self.done.set(true);
+        }
+
+        if page.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(page))
+        }
+    }
+
+    fn token(&self) -> String {
+        format!("{}:{}:{}", self.kind, self.scope, self.offset.get())
+    }
+}
+
+/// Reads a `kind:scope:offset` token, rejecting tokens taken from a cursor
+/// over a different list or filter.
+fn parse_token(kind: &str, scope: &str, token: &str) -> Result<u32, ApiError> {
+    let (token_scope, offset) = token
+        .strip_prefix(kind)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .and_then(|rest| rest.rsplit_once(':'))
+        .and_then(|(token_scope, offset)| Some((token_scope, offset.parse().ok()?)))
+        .ok_or_else(|| {
+            api_error(
+                ApiErrorCode::BadRequest,
+                &format!("Invalid {} cursor position '{}'", kind, token),
+            )
+        })?;
+
+    if token_scope != scope {
+        return Err(api_error(
+            ApiErrorCode::BadRequest,
+            &format!(
+                "Cursor position '{}' belongs to a different {} query",
+                token, kind
+            ),
+        ));
+    }
+
+    Ok(offset)
+}
+
+/// Short FNV-1a hash of `parts`, used as the scope of filter-based cursors.
+fn fingerprint(parts: &[&str]) -> String {
+    let mut hash: u32 = 0x811c_9dc5;
+    for part in parts {
+        for byte in part.bytes().chain([0]) {
+            hash ^= u32::from(byte);
+            hash = hash.wrapping_mul(0x0100_0193);
+        }
+    }
+    format!("{:08x}", hash)
+}
+
+// -------- List leads --------
+
+pub struct HeyReachListLeadsCursor {
+    http: HttpClient,
+    list_id: u64,
+    keyword: Option<String>,
+    position: Position,
+}
+
+impl HeyReachListLeadsCursor {
+    pub fn new(
+        http: HttpClient,
+        list_id: u64,
+        keyword: Option<String>,
+        page_size: Option<u32>,
+        position: Option<String>,
+    ) -> Result<Self, ApiError> {
+        Ok(HeyReachListLeadsCursor {
+            http,
+            list_id,
+            position: Position::new(
+                "list-leads",
+                format!(
+                    "{}.{}",
+                    list_id,
+                    fingerprint(&[keyword.as_deref().unwrap_or("")])
+                ),
+                0,
+                page_size.unwrap_or(MAX_PAGE_SIZE),
+                position,
+            )?,
+            keyword,
+        })
+    }
+}
+
+impl GuestListLeadsCursor for HeyReachListLeadsCursor {
+    fn next_page(&self) -> Result<Option<Vec<Lead>>, ApiError> {
+        self.position.next(|offset, limit| {
+            let page = client::lists_get_leads(
+                &self.http,
+                self.list_id,
+                offset,
+                limit,
+                self.keyword.clone(),
+            )?;
+            Ok((page.total_count, page.items))
+        })
+    }
+
+    fn position(&self) -> String {
+        self.position.token()
+    }
+
+    fn total_count(&self) -> Option<u32> {
+        self.position.total_count.get()
+    }
+}
+
+// -------- Campaigns --------
+
+pub struct HeyReachCampaignCursor {
+    http: HttpClient,
+    filter: CampaignFilter,
+    position: Position,
+}
+
+impl HeyReachCampaignCursor {
+    pub fn new(
+        http: HttpClient,
+        filter: CampaignFilter,
+        position: Option<String>,
+    ) -> Result<Self, ApiError> {
+        let position = Position::new(
+            "campaigns",
+            campaign_filter_scope(&filter),
+            filter.offset,
+            filter.limit,
+            position,
+        )?;
+        Ok(HeyReachCampaignCursor {
+            http,
+            filter,
+            position,
+        })
+    }
+}
+
+/// Fingerprint of everything in `filter` that selects campaigns; the offset
+/// and page size are left out since they do not change the result set.
+fn campaign_filter_scope(filter: &CampaignFilter) -> String {
+    let mut statuses: Vec<String> = filter
+        .statuses
+        .iter()
+        .map(client::campaign_status_to_string)
+        .collect();
+    statuses.sort();
+    statuses.dedup();
+    let mut account_ids = filter.account_ids.clone();
+    account_ids.sort_unstable();
+    account_ids.dedup();
+    let account_ids: Vec<String> = account_ids.iter().map(u32::to_string).collect();
+
+    fingerprint(&[
+        filter.keyword.as_deref().unwrap_or(""),
+        &statuses.join(","),
+        &account_ids.join(","),
+    ])
+}
+
+impl GuestCampaignCursor for HeyReachCampaignCursor {
+    fn next_page(&self) -> Result<Option<Vec<CampaignSummary>>, ApiError> {
+        self.position.next(|offset, limit| {
+            let page = client::campaigns_get_all(
+                &self.http,
+                CampaignFilter {
+                    offset,
+                    limit,
+                    ..self.filter.clone()
+                },
+            )?;
+            Ok((page.total_count, page.items))
+        })
+    }
+
+    fn position(&self) -> String {
+        self.position.token()
+    }
+
+    fn total_count(&self) -> Option<u32> {
+        self.position.total_count.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
+    use std::rc::Rc;
+
+    fn http() -> (Rc<MemoryTransport>, HttpClient) {
+        let transport = Rc::new(MemoryTransport::new());
+        let http = HttpClient::for_tests(transport.clone());
+        (transport, http)
+    }
+
+    fn campaign_filter(limit: u32, keyword: Option<&str>) -> CampaignFilter {
+        CampaignFilter {
+            offset: 0,
+            limit,
+            keyword: keyword.map(str::to_string),
+            statuses: vec![CampaignStatus::Active, CampaignStatus::Paused],
+            account_ids: vec![2, 1],
+        }
+    }
+
+    #[test]
+    fn zero_limit_uses_max_page_size() {
+        let (_, http) = http();
+        let cursor = HeyReachCampaignCursor::new(http, campaign_filter(0, None), None).unwrap();
+        assert_eq!(cursor.position.page_size, MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn position_resumes_same_list() {
+        let (_, http) = http();
+        let cursor = HeyReachListLeadsCursor::new(http.clone(), 42, None, None, None).unwrap();
+        cursor.position.offset.set(200);
+
+        let resumed =
+            HeyReachListLeadsCursor::new(http, 42, None, None, Some(cursor.position())).unwrap();
+        assert_eq!(resumed.position.offset.get(), 200);
+    }
+
+    #[test]
+    fn position_rejected_for_other_list_or_keyword() {
+        let (_, http) = http();
+        let token = HeyReachListLeadsCursor::new(http.clone(), 42, None, None, None)
+            .unwrap()
+            .position();
+
+        for (list_id, keyword) in [(43, None), (42, Some("cto".to_string()))] {
+            let error = HeyReachListLeadsCursor::new(
+                http.clone(),
+                list_id,
+                keyword,
+                None,
+                Some(token.clone()),
+            )
+            .err()
+            .unwrap();
+            assert_eq!(error.code, ApiErrorCode::BadRequest);
+        }
+    }
+
+    #[test]
+    fn campaign_position_tracks_filter() {
+        let (_, http) = http();
+        let token = HeyReachCampaignCursor::new(http.clone(), campaign_filter(10, None), None)
+            .unwrap()
+            .position();
+
+        let mut reordered = campaign_filter(50, None);
+        reordered.statuses.reverse();
+        reordered.account_ids.reverse();
+        assert!(HeyReachCampaignCursor::new(http.clone(), reordered, Some(token.clone())).is_ok());
+
+        let error = HeyReachCampaignCursor::new(http, campaign_filter(10, Some("q3")), Some(token))
+            .err()
+            .unwrap();
+        assert_eq!(error.code, ApiErrorCode::BadRequest);
+    }
+
+    #[test]
+    fn malformed_position_is_rejected() {
+        let (_, http) = http();
+        for token in ["list-leads:1200", "campaigns:0:5", "list-leads:42.x:abc"] {
+            assert!(HeyReachListLeadsCursor::new(
+                http.clone(),
+                42,
+                None,
+                None,
+                Some(token.to_string())
+            )
+            .is_err());
+        }
+    }
+
+    fn leads_page_json(total_count: u32, range: std::ops::Range<u32>) -> String {
+        let items: Vec<String> = range
+            .map(|i| {
+                format!(
+                    r#"{{"firstName":"F{}","lastName":"L{}","profileUrl":"https://www.linkedin.com/in/{}"}}"#,
+                    i, i, i
+                )
+            })
+            .collect();
+        format!(
+            r#"{{"totalCount":{},"items":[{}]}}"#,
+            total_count,
+            items.join(",")
+        )
+    }
+
+    fn requested_offsets(transport: &MemoryTransport) -> Vec<u64> {
+        transport
+            .requests()
+            .iter()
+            .map(|request| {
+                let body: serde_json::Value =
+                    serde_json::from_slice(request.body.as_deref().unwrap()).unwrap();
+                body["offset"].as_u64().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn next_page_advances_until_total_count() {
+        let (transport, http) = http();
+        transport.push_json(200, &leads_page_json(5, 0..2));
+        transport.push_json(200, &leads_page_json(5, 2..4));
+        transport.push_json(200, &leads_page_json(5, 4..5));
+        let cursor = HeyReachListLeadsCursor::new(http, 42, None, Some(2), None).unwrap();
+        assert_eq!(cursor.total_count(), None);
+
+        assert_eq!(cursor.next_page().unwrap().unwrap().len(), 2);
+        assert_eq!(cursor.total_count(), Some(5));
+        assert!(cursor.position().ends_with(":2"));
+        assert!(!cursor.position.done.get());
+
+        assert_eq!(cursor.next_page().unwrap().unwrap().len(), 2);
+        let last = cursor.next_page().unwrap().unwrap();
+        assert_eq!(last[0].profile_url, "https://www.linkedin.com/in/4");
+        assert!(cursor.position.done.get());
+        assert!(cursor.position().ends_with(":5"));
+
+        assert!(cursor.next_page().unwrap().is_none());
+        assert_eq!(requested_offsets(&transport), [0, 2, 4]);
+    }
+
+    #[test]
+    fn short_page_does_not_end_the_cursor() {
+        let (transport, http) = http();
+        transport.push_json(200, &leads_page_json(150, 0..60));
+        transport.push_json(200, &leads_page_json(150, 60..150));
+        let cursor = HeyReachListLeadsCursor::new(http, 42, None, None, None).unwrap();
+
+        assert_eq!(cursor.next_page().unwrap().unwrap().len(), 60);
+        assert!(!cursor.position.done.get());
+        assert_eq!(cursor.next_page().unwrap().unwrap().len(), 90);
+        assert!(cursor.next_page().unwrap().is_none());
+        assert_eq!(requested_offsets(&transport), [0, 60]);
+    }
+
+    #[test]
+    fn empty_page_ends_the_cursor() {
+        let (transport, http) = http();
+        transport.push_json(200, &leads_page_json(500, 0..0));
+        let cursor = HeyReachListLeadsCursor::new(http, 42, None, None, None).unwrap();
+
+        assert!(cursor.next_page().unwrap().is_none());
+        assert!(cursor.position.done.get());
+        assert_eq!(cursor.total_count(), Some(500));
+        assert!(cursor.next_page().unwrap().is_none());
+        assert_eq!(transport.requests().len(), 1);
+    }
+
+    #[test]
+    fn failed_fetch_keeps_position_and_can_be_repeated() {
+        let (transport, http) = http();
+        transport.push_json(200, &leads_page_json(4, 0..2));
+        transport.push_json(404, r#"{"detail":"list not found"}"#);
+        transport.push_json(200, &leads_page_json(4, 2..4));
+        let cursor = HeyReachListLeadsCursor::new(http, 42, None, Some(2), None).unwrap();
+
+        cursor.next_page().unwrap();
+        let before = cursor.position();
+
+        let error = cursor.next_page().unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::NotFound);
+        assert_eq!(cursor.position(), before);
+        assert!(!cursor.position.done.get());
+
+        assert_eq!(cursor.next_page().unwrap().unwrap().len(), 2);
+        assert!(cursor.position.done.get());
+        assert_eq!(requested_offsets(&transport), [0, 2, 2]);
+    }
+}
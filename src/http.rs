@@ -1,445 +1,539 @@
-use crate::exports::heyreach::client::api::{ApiError, ApiErrorCode};
-use crate::wasi::http::outgoing_handler;
-use crate::wasi::http::types::*;
-use crate::wasi::io::streams::StreamError;
+use crate::config::{self, HttpConfig};
+use crate::exports::heyreach::client::api::{
+    ApiError, ApiErrorCode, ClientMetrics, FieldError, Timeouts,
+};
+use crate::layers::{Auth, Logging, Metrics, RateLimited, Retrying};
+use crate::log::Logger;
+use crate::retry::{self, RetryMode};
+use crate::transport::{endpoint, Request, Response, Transport, WasiHttpTransport};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use std::cell::RefCell;
+use std::rc::Rc;
 
+#[derive(Debug, Clone, Copy)]
 pub enum HttpMethod {
     Get,
     Post,
+    Patch,
     Delete,
 }
 
-pub fn make_request<T: DeserializeOwned>(
-    method: HttpMethod,
-    path: &str,
-    api_key: &str,
-    body: Option<&impl Serialize>,
-) -> Result<T, ApiError> {
-    println!("[DEBUG] make_request called - path: {}", path);
-
-    let headers = Fields::new();
-
-    println!("[DEBUG] Creating headers...");
-    headers
-        .append(
-            &"content-type".to_string(),
-            &b"application/json; charset=utf-8".to_vec(),
-        )
-        .map_err(|e| {
-            println!("[ERROR] Failed to append content-type header: {:?}", e);
-            api_error(
-                ApiErrorCode::Unknown,
-                "failed to append content-type header",
-            )
-        })?;
-
-    headers
-        .append(&"x-api-key".to_string(), api_key.as_bytes())
-        .map_err(|e| {
-            println!("[ERROR] Failed to set API key header: {:?}", e);
-            api_error(ApiErrorCode::Unauthorized, "Failed to set API key header")
-        })?;
+/// Sends requests to the HeyReach API on behalf of one API key.
+///
+/// Every call goes through the same pipeline of layers (see `layers.rs`) on
+/// top of a `Transport`; clones share the pipeline and its metrics.
+#[derive(Clone)]
+pub struct HttpClient {
+    config: HttpConfig,
+    pipeline: Rc<dyn Transport>,
+    metrics: Rc<RefCell<ClientMetrics>>,
+    log: Logger,
+}
 
-    let outgoing_request = OutgoingRequest::new(headers);
+impl HttpClient {
+    pub fn new(api_key: String, config: HttpConfig) -> Self {
+        let transport = Rc::new(WasiHttpTransport::new(&config));
+        Self::with_transport(api_key, config, transport)
+    }
 
-    let method_value = match method {
-        HttpMethod::Get => Method::Get,
-        HttpMethod::Post => Method::Post,
-        HttpMethod::Delete => Method::Delete,
-    };
+    /// Builds the pipeline on top of an arbitrary transport, e.g. a
+    /// `MemoryTransport` under native `cargo test`.
+    pub fn with_transport(
+        api_key: String,
+        config: HttpConfig,
+        transport: Rc<dyn Transport>,
+    ) -> Self {
+        let metrics = Rc::new(RefCell::new(ClientMetrics {
+            requests: 0,
+            failures: 0,
+            retries: 0,
+            total_latency_ms: 0,
+        }));
+        let log = Logger::new(config.log_level);
+
+        let pipeline = Auth::new(
+            api_key.clone(),
+            Retrying::new(
+                config.retry,
+                log,
+                RateLimited::new(
+                    api_key,
+                    config.rate_limit,
+                    log,
+                    Metrics::new(metrics.clone(), Logging::new(log, transport)),
+                ),
+            ),
+        );
+
+        HttpClient {
+            config,
+            pipeline: Rc::new(pipeline),
+            metrics,
+            log,
+        }
+    }
 
-    println!("[DEBUG] Setting method: {:?}", method_value);
-    outgoing_request.set_method(&method_value).map_err(|e| {
-        println!("[ERROR] Failed to set method: {:?}", e);
-        api_error(ApiErrorCode::Unknown, "Failed to set method")
-    })?;
-
-    println!("[DEBUG] Setting path: {}", path);
-    outgoing_request
-        .set_path_with_query(Some(path))
-        .map_err(|e| {
-            println!("[ERROR] Failed to set path: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to set path")
-        })?;
+    /// Copy of this client with `timeouts` applied on top of its own.
+    pub fn with_timeouts(&self, timeouts: Timeouts) -> Result<Self, ApiError> {
+        let mut client = self.clone();
+        client.config.timeouts = config::merge_timeouts(&self.config.timeouts, timeouts)?;
+        Ok(client)
+    }
 
-    println!("[DEBUG] Setting scheme to HTTPS");
-    outgoing_request
-        .set_scheme(Some(&Scheme::Https))
-        .map_err(|e| {
-            println!("[ERROR] Failed to set scheme: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to set scheme")
-        })?;
+    pub fn metrics(&self) -> ClientMetrics {
+        *self.metrics.borrow()
+    }
 
-    println!("[DEBUG] Setting authority to api.heyreach.io");
-    outgoing_request
-        .set_authority(Some("api.heyreach.io"))
-        .map_err(|e| {
-            println!("[ERROR] Failed to set authority: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to set authority")
+    pub fn make_request<T: DeserializeOwned>(
+        &self,
+        method: HttpMethod,
+        path: &str,
+        retry: RetryMode,
+        body: Option<&impl Serialize>,
+    ) -> Result<T, ApiError> {
+        let response = self.execute(method, path, retry, body)?;
+
+        let response_text = std::str::from_utf8(&response.body).map_err(|e| {
+            self.log.error(
+                "invalid UTF-8 in response",
+                &[("endpoint", &endpoint(path)), ("error", &e)],
+            );
+            with_context(
+                api_error(ApiErrorCode::Decode, "Invalid UTF-8 in response"),
+                path,
+                Some(&response),
+            )
         })?;
 
-    if let Some(body_data) = body {
-        println!("[DEBUG] Serializing body...");
-        let body_bytes = serde_json::to_vec(body_data).map_err(|e| {
-            println!("[ERROR] Failed to serialize body: {}", e);
-            api_error(
-                ApiErrorCode::BadRequest,
-                &format!("Failed to serialize body: {}", e),
+        serde_json::from_str(response_text).map_err(|e| {
+            self.log.error(
+                "failed to parse response JSON",
+                &[("endpoint", &endpoint(path)), ("error", &e)],
+            );
+            with_context(
+                api_error(
+                    ApiErrorCode::Decode,
+                    &format!("Failed to parse response: {}", e),
+                ),
+                path,
+                Some(&response),
             )
-        })?;
+        })
+    }
 
-        println!("[DEBUG] Body size: {} bytes", body_bytes.len());
+    pub fn make_request_empty(
+        &self,
+        method: HttpMethod,
+        path: &str,
+        retry: RetryMode,
+        body: Option<&impl Serialize>,
+    ) -> Result<(), ApiError> {
+        // For successful responses we don't care about the body
+        self.execute(method, path, retry, body)?;
+        Ok(())
+    }
 
-        let outgoing_body = outgoing_request.body().map_err(|e| {
-            println!("[ERROR] Failed to get outgoing body: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to get outgoing body")
-        })?;
+    /// Serializes the body, sends the request through the pipeline and maps
+    /// error statuses to `ApiError`.
+    fn execute(
+        &self,
+        method: HttpMethod,
+        path: &str,
+        retry: RetryMode,
+        body: Option<&impl Serialize>,
+    ) -> Result<Response, ApiError> {
+        let body = match body {
+            Some(body_data) => Some(serde_json::to_vec(body_data).map_err(|e| {
+                self.log.error(
+                    "failed to serialize body",
+                    &[("endpoint", &endpoint(path)), ("error", &e)],
+                );
+                api_error(
+                    ApiErrorCode::BadRequest,
+                    &format!("Failed to serialize body: {}", e),
+                )
+            })?),
+            None => None,
+        };
 
-        let body_stream = outgoing_body.write().map_err(|e| {
-            println!("[ERROR] Failed to get body stream: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to get body stream")
-        })?;
+        let mut request = Request {
+            method,
+            path: path.to_string(),
+            headers: vec![(
+                "content-type".to_string(),
+                b"application/json; charset=utf-8".to_vec(),
+            )],
+            body,
+            timeouts: self.config.timeouts,
+            retry,
+            attempt: 1,
+        };
 
-        println!("[DEBUG] Writing body...");
-        body_stream
-            .blocking_write_and_flush(&body_bytes)
-            .map_err(|e| {
-                println!("[ERROR] Failed to write body: {:?}", e);
-                api_error(ApiErrorCode::Unknown, "Failed to write body")
-            })?;
-
-        drop(body_stream);
-        OutgoingBody::finish(outgoing_body, None).map_err(|e| {
-            println!("[ERROR] Failed to finish body: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to finish body")
-        })?;
-    } else {
-        println!("[DEBUG] No body to send");
+        let response = self
+            .pipeline
+            .send(&mut request)
+            .map_err(|e| with_context(e.error, path, None))?;
+        if response.status >= 400 {
+            let error = with_context(error_from_response(&response), path, Some(&response));
+            self.log.debug(
+                "API returned an error",
+                &[
+                    ("endpoint", &request.endpoint()),
+                    ("status", &response.status),
+                    ("body_bytes", &response.body.len()),
+                    // The message may be the raw response body, so only the
+                    // code is logged.
+                    ("code", &format!("{:?}", error.code)),
+                ],
+            );
+            return Err(error);
+        }
+        Ok(response)
     }
+}
 
-    println!("[DEBUG] Sending request...");
-    let future_response = outgoing_handler::handle(outgoing_request, None).map_err(|e| {
-        println!("[ERROR] Failed to send request: {:?}", e);
-        api_error(ApiErrorCode::Unknown, "Failed to send request")
-    })?;
-
-    println!("[DEBUG] Waiting for response...");
-    future_response.subscribe().block();
-
-    println!("[DEBUG] Getting response...");
-    let incoming_response = future_response
-        .get()
-        .ok_or_else(|| {
-            println!("[ERROR] Request not completed");
-            api_error(ApiErrorCode::Unknown, "Request not completed")
-        })?
-        .map_err(|e| {
-            println!("[ERROR] Request failed: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Request failed")
-        })?
-        .map_err(|e| {
-            println!("[ERROR] Request error: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Request error")
-        })?;
+fn error_from_response(response: &Response) -> ApiError {
+    let status = response.status;
+    let error_code = match status {
+        401 => ApiErrorCode::Unauthorized,
+        403 => ApiErrorCode::Forbidden,
+        404 => ApiErrorCode::NotFound,
+        429 => ApiErrorCode::TooManyRequests,
+        400 => ApiErrorCode::BadRequest,
+        422 => ApiErrorCode::Validation,
+        500..=599 => ApiErrorCode::ServerError,
+        _ => ApiErrorCode::Unknown,
+    };
 
-    let status = incoming_response.status();
-    println!("[DEBUG] Response status: {}", status);
-
-    println!("[DEBUG] Getting response body...");
-    let incoming_body = incoming_response.consume().map_err(|e| {
-        println!("[ERROR] Failed to get response body: {:?}", e);
-        api_error(ApiErrorCode::Unknown, "Failed to get response body")
-    })?;
-
-    println!("[DEBUG] Getting body stream...");
-    let body_stream = incoming_body.stream().map_err(|e| {
-        println!("[ERROR] Failed to get body stream: {:?}", e);
-        api_error(ApiErrorCode::Unknown, "Failed to get body stream")
-    })?;
-
-    let mut response_bytes = Vec::new();
-    println!("[DEBUG] Reading response chunks...");
-    loop {
-        match body_stream.blocking_read(8192) {
-            Ok(chunk) => {
-                if chunk.is_empty() {
-                    println!("[DEBUG] Finished reading response");
-                    break;
-                }
-                println!("[DEBUG] Read chunk of {} bytes", chunk.len());
-                response_bytes.extend_from_slice(&chunk);
-            }
-            Err(StreamError::Closed) => {
-                println!("[DEBUG] Stream closed (end of response)");
-                break;
-            }
-            Err(e) => {
-                println!("[ERROR] Failed to read response chunk: {:?}", e);
-                return Err(api_error(ApiErrorCode::Unknown, "Failed to read response"));
+    let mut field_errors = Vec::new();
+    let error_message = if let Ok(text) = String::from_utf8(response.body.clone()) {
+        if let Ok(error_json) = serde_json::from_str::<serde_json::Value>(&text) {
+            if let Some(errors) = error_json.get("errors") {
+                field_errors = parse_field_errors(errors);
             }
+            error_json
+                .get("detail")
+                .or_else(|| error_json.get("errorMessage"))
+                .or_else(|| error_json.get("message"))
+                .or_else(|| error_json.get("title"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| format!("HTTP {}", status))
+        } else {
+            // Plain-text and HTML bodies (e.g. a proxy's 502 page) can be
+            // any size; they are kept, truncated, in `raw_body` instead.
+            format!("HTTP {}", status)
         }
-    }
+    } else {
+        format!("HTTP {}", status)
+    };
 
-    println!("[DEBUG] Total response bytes: {}", response_bytes.len());
-    drop(body_stream);
-
-    if status >= 400 {
-        println!("[DEBUG] Error status code detected: {}", status);
-        let error_code = match status {
-            401 => ApiErrorCode::Unauthorized,
-            404 => ApiErrorCode::NotFound,
-            429 => ApiErrorCode::TooManyRequests,
-            400 => ApiErrorCode::BadRequest,
-            422 => ApiErrorCode::Validation,
-            _ => ApiErrorCode::Unknown,
-        };
+    let mut error = api_error(error_code, &error_message);
+    error.field_errors = field_errors;
+    error
+}
+
+/// Reads the ASP.NET problem-details `errors` map of field name to messages.
+/// A message given as a single string instead of a list is accepted too.
+fn parse_field_errors(errors: &serde_json::Value) -> Vec<FieldError> {
+    let Some(errors) = errors.as_object() else {
+        return Vec::new();
+    };
 
-        let error_message = if let Ok(text) = String::from_utf8(response_bytes.clone()) {
-            println!("[DEBUG] Error response body: {}", text);
-            if let Ok(error_json) = serde_json::from_str::<serde_json::Value>(&text) {
-                error_json
-                    .get("detail")
-                    .or_else(|| error_json.get("errorMessage"))
-                    .or_else(|| error_json.get("message"))
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| format!("HTTP {}", status))
-            } else {
-                text
+    errors
+        .iter()
+        .map(|(field, messages)| {
+            let messages = match messages {
+                serde_json::Value::Array(items) => items
+                    .iter()
+                    .filter_map(|m| m.as_str())
+                    .map(|m| m.to_string())
+                    .collect(),
+                serde_json::Value::String(m) => vec![m.clone()],
+                _ => Vec::new(),
+            };
+            FieldError {
+                field: field.clone(),
+                messages,
             }
-        } else {
-            println!("[DEBUG] Error response body is not valid UTF-8");
-            format!("HTTP {}", status)
-        };
+        })
+        .collect()
+}
+
+/// Headers HeyReach and common proxies use to identify a request.
+const REQUEST_ID_HEADERS: &[&str] = &["x-request-id", "request-id", "x-correlation-id"];
 
-        return Err(api_error(error_code, &error_message));
+/// Longest `raw-body` kept on an error, in bytes.
+const MAX_RAW_BODY_BYTES: usize = 2048;
+
+/// Fills in the request and response details of `error` that are not set yet.
+fn with_context(mut error: ApiError, path: &str, response: Option<&Response>) -> ApiError {
+    if error.endpoint.is_none() {
+        error.endpoint = Some(endpoint(path).to_string());
+    }
+
+    if let Some(response) = response {
+        error.status = error.status.or(Some(response.status));
+        error.request_id = error.request_id.or_else(|| request_id(response));
+        error.retry_after_ms = error.retry_after_ms.or_else(|| {
+            response
+                .header("retry-after")
+                .and_then(retry::parse_retry_after_ms)
+        });
+        if error.raw_body.is_none() && !response.body.is_empty() {
+            error.raw_body = Some(truncate_body(&response.body));
+        }
     }
 
-    println!("[DEBUG] Converting response to UTF-8...");
-    let response_text = String::from_utf8(response_bytes).map_err(|e| {
-        println!("[ERROR] Invalid UTF-8 in response: {:?}", e);
-        api_error(ApiErrorCode::Unknown, "Invalid UTF-8 in response")
-    })?;
-
-    println!("[DEBUG] Response text: '{}'", response_text);
-    println!("[DEBUG] Parsing JSON...");
-
-    serde_json::from_str(&response_text).map_err(|e| {
-        println!("[ERROR] Failed to parse response JSON: {}", e);
-        println!("[ERROR] Response was: '{}'", response_text);
-        api_error(
-            ApiErrorCode::Unknown,
-            &format!("Failed to parse response: {}", e),
-        )
-    })
+    error
 }
 
-pub fn make_request_empty(
-    method: HttpMethod,
-    path: &str,
-    api_key: &str,
-    body: Option<&impl Serialize>,
-) -> Result<(), ApiError> {
-    println!("[DEBUG] make_request_empty called - path: {}", path);
-
-    let headers = Fields::new();
-
-    println!("[DEBUG] Creating headers...");
-    headers
-        .append(
-            &"content-type".to_string(),
-            &b"application/json; charset=utf-8".to_vec(),
-        )
-        .map_err(|e| {
-            println!("[ERROR] Failed to append content-type header: {:?}", e);
-            api_error(
-                ApiErrorCode::Unknown,
-                "failed to append content-type header",
-            )
-        })?;
+/// Request id from the response headers, or the problem-details `traceId`.
+fn request_id(response: &Response) -> Option<String> {
+    REQUEST_ID_HEADERS
+        .iter()
+        .find_map(|name| response.header(name))
+        .map(|id| id.to_string())
+        .or_else(|| {
+            serde_json::from_slice::<serde_json::Value>(&response.body)
+                .ok()?
+                .get("traceId")?
+                .as_str()
+                .map(|id| id.to_string())
+        })
+}
 
-    headers
-        .append(&"x-api-key".to_string(), api_key.as_bytes())
-        .map_err(|e| {
-            println!("[ERROR] Failed to set API key header: {:?}", e);
-            api_error(ApiErrorCode::Unauthorized, "Failed to set API key header")
-        })?;
+fn truncate_body(body: &[u8]) -> String {
+    let text = String::from_utf8_lossy(body);
+    if text.len() <= MAX_RAW_BODY_BYTES {
+        return text.into_owned();
+    }
 
-    let outgoing_request = OutgoingRequest::new(headers);
+    let mut end = MAX_RAW_BODY_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &text[..end])
+}
 
-    let method_value = match method {
-        HttpMethod::Get => Method::Get,
-        HttpMethod::Post => Method::Post,
-        HttpMethod::Delete => Method::Delete,
-    };
+pub fn api_error(code: ApiErrorCode, message: &str) -> ApiError {
+    ApiError {
+        code,
+        message: message.to_string(),
+        status: None,
+        endpoint: None,
+        request_id: None,
+        retry_after_ms: None,
+        raw_body: None,
+        field_errors: Vec::new(),
+    }
+}
 
-    println!("[DEBUG] Setting method: {:?}", method_value);
-    outgoing_request.set_method(&method_value).map_err(|e| {
-        println!("[ERROR] Failed to set method: {:?}", e);
-        api_error(ApiErrorCode::Unknown, "Failed to set method")
-    })?;
-
-    println!("[DEBUG] Setting path: {}", path);
-    outgoing_request
-        .set_path_with_query(Some(path))
-        .map_err(|e| {
-            println!("[ERROR] Failed to set path: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to set path")
-        })?;
+#[cfg(test)]
+impl HttpClient {
+    /// Client over `transport` with 1 ms backoffs, no rate limit and no
+    /// logging, for tests.
+    pub fn for_tests(transport: Rc<crate::transport::MemoryTransport>) -> Self {
+        use crate::exports::heyreach::client::api::{
+            LogLevel, RateLimit, RateLimitMode, RetryPolicy,
+        };
 
-    println!("[DEBUG] Setting scheme to HTTPS");
-    outgoing_request
-        .set_scheme(Some(&Scheme::Https))
-        .map_err(|e| {
-            println!("[ERROR] Failed to set scheme: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to set scheme")
-        })?;
+        let config = HttpConfig {
+            retry: RetryPolicy {
+                max_attempts: 3,
+                initial_backoff_ms: 1,
+                max_backoff_ms: 1_000,
+                jitter: false,
+                retry_writes: false,
+            },
+            rate_limit: RateLimit {
+                requests_per_minute: 0,
+                burst: None,
+                mode: RateLimitMode::FailFast,
+            },
+            log_level: LogLevel::Off,
+            ..HttpConfig::default()
+        };
+        HttpClient::with_transport("test-key".to_string(), config, transport)
+    }
+}
 
-    println!("[DEBUG] Setting authority to api.heyreach.io");
-    outgoing_request
-        .set_authority(Some("api.heyreach.io"))
-        .map_err(|e| {
-            println!("[ERROR] Failed to set authority: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to set authority")
-        })?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::MemoryTransport;
 
-    if let Some(body_data) = body {
-        println!("[DEBUG] Serializing body...");
-        let body_bytes = serde_json::to_vec(body_data).map_err(|e| {
-            println!("[ERROR] Failed to serialize body: {}", e);
-            api_error(
-                ApiErrorCode::BadRequest,
-                &format!("Failed to serialize body: {}", e),
-            )
-        })?;
+    fn get(client: &HttpClient, retry: RetryMode) -> Result<serde_json::Value, ApiError> {
+        client.make_request(HttpMethod::Get, "/api/public/test?x=1", retry, None::<&()>)
+    }
 
-        println!("[DEBUG] Body size: {} bytes", body_bytes.len());
+    #[test]
+    fn sends_api_key_header() {
+        let transport = Rc::new(MemoryTransport::new());
+        transport.push_json(200, "{}");
+        let client = HttpClient::for_tests(transport.clone());
+
+        get(&client, RetryMode::Read).unwrap();
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 1);
+        let api_key = requests[0]
+            .headers
+            .iter()
+            .find(|(name, _)| name == "x-api-key")
+            .map(|(_, value)| value.clone());
+        assert_eq!(api_key, Some(b"test-key".to_vec()));
+    }
 
-        let outgoing_body = outgoing_request.body().map_err(|e| {
-            println!("[ERROR] Failed to get outgoing body: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to get outgoing body")
-        })?;
+    #[test]
+    fn maps_status_to_error_code() {
+        let cases = [
+            (400, ApiErrorCode::BadRequest),
+            (401, ApiErrorCode::Unauthorized),
+            (403, ApiErrorCode::Forbidden),
+            (404, ApiErrorCode::NotFound),
+            (422, ApiErrorCode::Validation),
+            (500, ApiErrorCode::ServerError),
+            (418, ApiErrorCode::Unknown),
+        ];
+
+        for (status, code) in cases {
+            let transport = Rc::new(MemoryTransport::new());
+            transport.push_json(status, r#"{"detail":"nope"}"#);
+            let client = HttpClient::for_tests(transport);
+
+            let error = get(&client, RetryMode::Read).unwrap_err();
+            assert_eq!(error.code, code, "status {}", status);
+            assert_eq!(error.status, Some(status));
+            assert_eq!(error.message, "nope");
+            assert_eq!(error.endpoint.as_deref(), Some("/api/public/test"));
+        }
+    }
 
-        let body_stream = outgoing_body.write().map_err(|e| {
-            println!("[ERROR] Failed to get body stream: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to get body stream")
-        })?;
+    #[test]
+    fn non_json_error_body_is_not_the_message() {
+        let page = format!(
+            "<html><body><h1>502 Bad Gateway</h1>{}</body></html>",
+            "x".repeat(10_000)
+        );
+        let transport = Rc::new(MemoryTransport::new());
+        transport.push_status(404, &[("content-type", "text/html")], &page);
+        let client = HttpClient::for_tests(transport);
+
+        let error = get(&client, RetryMode::Read).unwrap_err();
+        assert_eq!(error.message, "HTTP 404");
+        let raw_body = error.raw_body.unwrap();
+        assert!(raw_body.starts_with("<html>"));
+        assert_eq!(raw_body.len(), MAX_RAW_BODY_BYTES + "...".len());
+    }
 
-        println!("[DEBUG] Writing body...");
-        body_stream
-            .blocking_write_and_flush(&body_bytes)
-            .map_err(|e| {
-                println!("[ERROR] Failed to write body: {:?}", e);
-                api_error(ApiErrorCode::Unknown, "Failed to write body")
-            })?;
-
-        drop(body_stream);
-        OutgoingBody::finish(outgoing_body, None).map_err(|e| {
-            println!("[ERROR] Failed to finish body: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to finish body")
-        })?;
-    } else {
-        println!("[DEBUG] No body to send");
+    #[test]
+    fn reads_field_errors_and_request_id() {
+        let transport = Rc::new(MemoryTransport::new());
+        transport.push_json(
+            400,
+            r#"{"title":"Invalid","traceId":"abc","errors":{"Leads[0].EmailAddress":["bad"],"Name":"missing"}}"#,
+        );
+        let client = HttpClient::for_tests(transport);
+
+        let error = get(&client, RetryMode::Read).unwrap_err();
+        assert_eq!(error.message, "Invalid");
+        assert_eq!(error.request_id.as_deref(), Some("abc"));
+        assert_eq!(error.field_errors.len(), 2);
+        assert!(error
+            .field_errors
+            .iter()
+            .any(|f| f.field == "Name" && f.messages == ["missing"]));
     }
 
-    println!("[DEBUG] Sending request...");
-    let future_response = outgoing_handler::handle(outgoing_request, None).map_err(|e| {
-        println!("[ERROR] Failed to send request: {:?}", e);
-        api_error(ApiErrorCode::Unknown, "Failed to send request")
-    })?;
-
-    println!("[DEBUG] Waiting for response...");
-    future_response.subscribe().block();
-
-    println!("[DEBUG] Getting response...");
-    let incoming_response = future_response
-        .get()
-        .ok_or_else(|| {
-            println!("[ERROR] Request not completed");
-            api_error(ApiErrorCode::Unknown, "Request not completed")
-        })?
-        .map_err(|e| {
-            println!("[ERROR] Request failed: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Request failed")
-        })?
-        .map_err(|e| {
-            println!("[ERROR] Request error: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Request error")
-        })?;
+    #[test]
+    fn retries_503_with_backoff() {
+        let transport = Rc::new(MemoryTransport::new());
+        transport.push_status(503, &[], "");
+        transport.push_json(200, r#"{"ok":true}"#);
+        let client = HttpClient::for_tests(transport.clone());
+
+        let value = get(&client, RetryMode::Read).unwrap();
+        assert_eq!(value["ok"], true);
+        assert_eq!(transport.requests().len(), 2);
+
+        let metrics = client.metrics();
+        assert_eq!(metrics.requests, 2);
+        assert_eq!(metrics.retries, 1);
+        assert_eq!(metrics.failures, 1);
+    }
 
-    let status = incoming_response.status();
-    println!("[DEBUG] Response status: {}", status);
+    #[test]
+    fn retries_429_after_retry_after() {
+        let transport = Rc::new(MemoryTransport::new());
+        transport.push_status(429, &[("Retry-After", "0")], "");
+        transport.push_json(200, "{}");
+        let client = HttpClient::for_tests(transport.clone());
 
-    // Check status first before trying to read body
-    if status >= 400 {
-        println!("[DEBUG] Error status code detected: {}", status);
+        get(&client, RetryMode::Read).unwrap();
+        assert_eq!(transport.requests().len(), 2);
+    }
 
-        // Try to read error body
-        let incoming_body = incoming_response.consume().map_err(|e| {
-            println!("[ERROR] Failed to get response body: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to get response body")
-        })?;
+    #[test]
+    fn gives_up_when_retry_after_exceeds_max_backoff() {
+        let transport = Rc::new(MemoryTransport::new());
+        transport.push_status(429, &[("retry-after", "120")], "");
+        let client = HttpClient::for_tests(transport.clone());
 
-        let body_stream = incoming_body.stream().map_err(|e| {
-            println!("[ERROR] Failed to get body stream: {:?}", e);
-            api_error(ApiErrorCode::Unknown, "Failed to get body stream")
-        })?;
+        let error = get(&client, RetryMode::Read).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::TooManyRequests);
+        assert_eq!(error.retry_after_ms, Some(120_000));
+        assert_eq!(transport.requests().len(), 1);
+    }
 
-        let mut response_bytes = Vec::new();
-        loop {
-            match body_stream.blocking_read(8192) {
-                Ok(chunk) if chunk.is_empty() => break,
-                Ok(chunk) => response_bytes.extend_from_slice(&chunk),
-                Err(_) => break, // Stream closed or error, just use what we have
-            }
+    #[test]
+    fn stops_after_max_attempts() {
+        let transport = Rc::new(MemoryTransport::new());
+        for _ in 0..3 {
+            transport.push_status(502, &[], "");
         }
+        let client = HttpClient::for_tests(transport.clone());
 
-        drop(body_stream);
-
-        let error_code = match status {
-            401 => ApiErrorCode::Unauthorized,
-            404 => ApiErrorCode::NotFound,
-            429 => ApiErrorCode::TooManyRequests,
-            400 => ApiErrorCode::BadRequest,
-            422 => ApiErrorCode::Validation,
-            _ => ApiErrorCode::Unknown,
-        };
+        let error = get(&client, RetryMode::Read).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::ServerError);
+        assert_eq!(transport.requests().len(), 3);
+        assert_eq!(transport.requests()[2].attempt, 3);
+    }
 
-        let error_message = if let Ok(text) = String::from_utf8(response_bytes.clone()) {
-            println!("[DEBUG] Error response body: {}", text);
-            if let Ok(error_json) = serde_json::from_str::<serde_json::Value>(&text) {
-                error_json
-                    .get("detail")
-                    .or_else(|| error_json.get("errorMessage"))
-                    .or_else(|| error_json.get("message"))
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string())
-                    .unwrap_or_else(|| format!("HTTP {}", status))
-            } else {
-                text
-            }
-        } else {
-            println!("[DEBUG] Error response body is not valid UTF-8");
-            format!("HTTP {}", status)
-        };
+    #[test]
+    fn never_mode_is_not_retried() {
+        let transport = Rc::new(MemoryTransport::new());
+        transport.push_status(503, &[], "");
+        transport.push_json(200, "{}");
+        let client = HttpClient::for_tests(transport.clone());
 
-        return Err(api_error(error_code, &error_message));
+        let error = get(&client, RetryMode::Never).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::ServerError);
+        assert_eq!(transport.requests().len(), 1);
     }
 
-    // For successful responses (status < 400), we don't need to read the body
-    // Just consume it to clean up resources, but ignore any errors
-    println!("[DEBUG] Success status, consuming body (but ignoring content)...");
-    let _ = incoming_response.consume();
+    #[test]
+    fn writes_are_not_retried_by_default() {
+        let transport = Rc::new(MemoryTransport::new());
+        transport.push_status(503, &[], "");
+        let client = HttpClient::for_tests(transport.clone());
 
-    println!("[DEBUG] Success! Returning Ok(())");
-    Ok(())
-}
+        get(&client, RetryMode::Write).unwrap_err();
+        assert_eq!(transport.requests().len(), 1);
+    }
 
-fn api_error(code: ApiErrorCode, message: &str) -> ApiError {
-    ApiError {
-        code,
-        message: message.to_string(),
+    #[test]
+    fn retries_transient_transport_errors() {
+        let transport = Rc::new(MemoryTransport::new());
+        transport.push(Err(crate::transport::TransportError::transient(
+            ApiErrorCode::Network,
+            "reset",
+        )));
+        transport.push_json(200, "{}");
+        let client = HttpClient::for_tests(transport.clone());
+
+        get(&client, RetryMode::Read).unwrap();
+        assert_eq!(transport.requests().len(), 2);
     }
 }
@@ -0,0 +1,234 @@
+//! Cross-cutting request behaviour, each implemented as a `Transport` that
+//! wraps another one. `HttpClient` stacks them as
+//! `Auth -> Retrying -> RateLimited -> Metrics -> Logging -> transport`.
+
+use crate::clock;
+use crate::exports::heyreach::client::api::{ClientMetrics, RateLimit, RetryPolicy};
+use crate::log::{self, Logger};
+use crate::ratelimit;
+use crate::retry;
+use crate::transport::{Request, Response, Transport, TransportError};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// -------- Auth --------
+
+/// Adds the `x-api-key` header.
+pub struct Auth<T> {
+    api_key: String,
+    inner: T,
+}
+
+impl<T> Auth<T> {
+    pub fn new(api_key: String, inner: T) -> Self {
+        Auth { api_key, inner }
+    }
+}
+
+impl<T: Transport> Transport for Auth<T> {
+    fn send(&self, request: &mut Request) -> Result<Response, TransportError> {
+        request
+            .headers
+            .push(("x-api-key".to_string(), self.api_key.as_bytes().to_vec()));
+        self.inner.send(request)
+    }
+}
+
+// -------- Retries --------
+
+/// Retries 429s, transient 5xx responses and transient transport errors
+/// according to the request's `RetryMode` and the client's policy.
+pub struct Retrying<T> {
+    policy: RetryPolicy,
+    log: Logger,
+    inner: T,
+}
+
+impl<T> Retrying<T> {
+    pub fn new(policy: RetryPolicy, log: Logger, inner: T) -> Self {
+        Retrying { policy, log, inner }
+    }
+}
+
+impl<T: Transport> Transport for Retrying<T> {
+    fn send(&self, request: &mut Request) -> Result<Response, TransportError> {
+        let max_attempts = request.retry.max_attempts(&self.policy);
+        request.attempt = 1;
+
+        loop {
+            let outcome = self.inner.send(request);
+
+            let delay_ms = match &outcome {
+                Ok(response) if retry::is_retryable_status(response.status) => {
+                    match response
+                        .header("retry-after")
+                        .and_then(retry::parse_retry_after_ms)
+                    {
+                        // Waiting less than the server asked for would just
+                        // earn another 429, so give up instead.
+                        Some(ms) if ms > self.policy.max_backoff_ms as u64 => None,
+                        Some(ms) => Some(ms),
+                        None => Some(retry::backoff_ms(&self.policy, request.attempt)),
+                    }
+                }
+                Err(e) if e.transient => Some(retry::backoff_ms(&self.policy, request.attempt)),
+                _ => None,
+            };
+
+            match delay_ms {
+                Some(delay_ms) if request.attempt < max_attempts => {
+                    self.log.warn(
+                        "retrying request",
+                        &[
+                            ("endpoint", &request.endpoint()),
+                            ("attempt", &request.attempt),
+                            ("max_attempts", &max_attempts),
+                            ("delay_ms", &delay_ms),
+                        ],
+                    );
+                    clock::sleep_ms(delay_ms);
+                    request.attempt += 1;
+                }
+                _ => return outcome,
+            }
+        }
+    }
+}
+
+// -------- Rate limiting --------
+
+/// Takes a token from the API key's shared bucket before every attempt.
+pub struct RateLimited<T> {
+    api_key: String,
+    limit: RateLimit,
+    log: Logger,
+    inner: T,
+}
+
+impl<T> RateLimited<T> {
+    pub fn new(api_key: String, limit: RateLimit, log: Logger, inner: T) -> Self {
+        RateLimited {
+            api_key,
+            limit,
+            log,
+            inner,
+        }
+    }
+}
+
+impl<T: Transport> Transport for RateLimited<T> {
+    fn send(&self, request: &mut Request) -> Result<Response, TransportError> {
+        ratelimit::acquire(&self.api_key, &self.limit, &self.log).map_err(|error| {
+            TransportError {
+                error,
+                transient: false,
+            }
+        })?;
+        self.inner.send(request)
+    }
+}
+
+// -------- Metrics --------
+
+/// Counts attempts, failures, retries and latency into per-client counters.
+pub struct Metrics<T> {
+    counters: Rc<RefCell<ClientMetrics>>,
+    inner: T,
+}
+
+impl<T> Metrics<T> {
+    pub fn new(counters: Rc<RefCell<ClientMetrics>>, inner: T) -> Self {
+        Metrics { counters, inner }
+    }
+}
+
+impl<T: Transport> Transport for Metrics<T> {
+    fn send(&self, request: &mut Request) -> Result<Response, TransportError> {
+        let started = clock::now_ns();
+        let outcome = self.inner.send(request);
+        let elapsed_ms = clock::now_ns().saturating_sub(started) / 1_000_000;
+
+        let mut counters = self.counters.borrow_mut();
+        counters.requests += 1;
+        counters.total_latency_ms += elapsed_ms;
+        if request.attempt > 1 {
+            counters.retries += 1;
+        }
+        if !matches!(&outcome, Ok(response) if response.status < 400) {
+            counters.failures += 1;
+        }
+
+        outcome
+    }
+}
+
+// -------- Logging --------
+
+/// Logs one line per attempt with its endpoint, status, latency and attempt
+/// number, plus redacted headers and body sizes at `trace`.
+pub struct Logging<T> {
+    log: Logger,
+    inner: T,
+}
+
+impl<T> Logging<T> {
+    pub fn new(log: Logger, inner: T) -> Self {
+        Logging { log, inner }
+    }
+}
+
+impl<T: Transport> Transport for Logging<T> {
+    fn send(&self, request: &mut Request) -> Result<Response, TransportError> {
+        let method = format!("{:?}", request.method).to_uppercase();
+
+        self.log.trace(
+            "sending request",
+            &[
+                ("method", &method),
+                ("endpoint", &request.endpoint()),
+                ("attempt", &request.attempt),
+                ("headers", &log::headers(&request.headers)),
+                ("body_bytes", &request.body.as_ref().map_or(0, Vec::len)),
+            ],
+        );
+
+        let started = clock::now_ns();
+        let outcome = self.inner.send(request);
+        let latency_ms = clock::now_ns().saturating_sub(started) / 1_000_000;
+
+        match &outcome {
+            Ok(response) => {
+                self.log.debug(
+                    "request completed",
+                    &[
+                        ("method", &method),
+                        ("endpoint", &request.endpoint()),
+                        ("status", &response.status),
+                        ("latency_ms", &latency_ms),
+                        ("attempt", &request.attempt),
+                    ],
+                );
+                self.log.trace(
+                    "response received",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("headers", &log::headers(&response.headers)),
+                        ("body_bytes", &response.body.len()),
+                    ],
+                );
+            }
+            Err(e) => self.log.warn(
+                "request failed",
+                &[
+                    ("method", &method),
+                    ("endpoint", &request.endpoint()),
+                    ("latency_ms", &latency_ms),
+                    ("attempt", &request.attempt),
+                    ("error", &e.error.message),
+                ],
+            ),
+        }
+
+        outcome
+    }
+}
@@ -1,23 +1,66 @@
+// `ApiError` is generated from the WIT `api-error` record and returned by
+// value across the component boundary, so it cannot be boxed.
+#![allow(clippy::result_large_err)]
+
 mod client;
+mod clock;
+mod config;
+mod cursor;
 mod http;
+mod layers;
+mod log;
 mod models;
+mod ratelimit;
+#[cfg(feature = "webhook-receiver")]
+mod receiver;
+mod retry;
+mod transport;
+#[cfg(feature = "webhook-receiver")]
+mod verify;
 
+use cursor::{HeyReachCampaignCursor, HeyReachListLeadsCursor};
 use exports::heyreach::client::api::*;
+use http::HttpClient;
 
+#[cfg(not(feature = "webhook-receiver"))]
 wit_bindgen::generate!({
     world: "heyreach-client",
     path: "wit",
     generate_all,
 });
 
+// The receiver world includes `heyreach-client`, so the same `Component`
+// also serves `wasi:http/incoming-handler` (see `receiver.rs`).
+#[cfg(feature = "webhook-receiver")]
+wit_bindgen::generate!({
+    world: "heyreach-webhook-receiver",
+    path: "wit",
+    generate_all,
+});
+
 struct Component;
 
 export!(Component);
 
+/// Builds a single-use client for the free functions from the settings
+/// passed to `configure`.
+fn default_client(api_key: String) -> HttpClient {
+    HttpClient::new(api_key, config::current())
+}
+
 impl Guest for Component {
+    type Client = HeyReachClient;
+    type ListLeadsCursor = HeyReachListLeadsCursor;
+    type CampaignCursor = HeyReachCampaignCursor;
+
+    // -------- Configuration --------
+    fn configure(config: ClientConfig) -> Result<(), ApiError> {
+        config::configure(config)
+    }
+
     // -------- Auth --------
     fn check_api_key(api_key: String) -> Result<(), ApiError> {
-        client::check_api_key(&api_key)
+        client::check_api_key(&default_client(api_key))
     }
 
     // -------- Campaigns --------
@@ -25,42 +68,42 @@ impl Guest for Component {
         api_key: String,
         filter: CampaignFilter,
     ) -> Result<CampaignPage, ApiError> {
-        client::campaigns_get_all(&api_key, filter)
+        client::campaigns_get_all(&default_client(api_key), filter)
     }
 
     fn campaigns_get_by_id(api_key: String, campaign_id: u64) -> Result<CampaignSummary, ApiError> {
-        client::campaigns_get_by_id(&api_key, campaign_id)
+        client::campaigns_get_by_id(&default_client(api_key), campaign_id)
     }
 
     fn campaigns_resume(api_key: String, campaign_id: u64) -> Result<(), ApiError> {
-        client::campaigns_resume(&api_key, campaign_id)
+        client::campaigns_resume(&default_client(api_key), campaign_id)
     }
 
     fn campaigns_pause(api_key: String, campaign_id: u64) -> Result<(), ApiError> {
-        client::campaigns_pause(&api_key, campaign_id)
+        client::campaigns_pause(&default_client(api_key), campaign_id)
     }
 
     fn campaigns_add_leads(
         api_key: String,
         payload: CampaignAddLeadsRequest,
     ) -> Result<u32, ApiError> {
-        client::campaigns_add_leads(&api_key, payload)
+        client::campaigns_add_leads(&default_client(api_key), payload)
     }
 
     fn campaigns_add_leads_v2(
         api_key: String,
         payload: CampaignAddLeadsRequest,
     ) -> Result<CampaignAddLeadsV2Result, ApiError> {
-        client::campaigns_add_leads_v2(&api_key, payload)
+        client::campaigns_add_leads_v2(&default_client(api_key), payload)
     }
 
     // -------- Lists --------
     fn lists_get_all(api_key: String, filter: ListGetAllFilter) -> Result<ListPage, ApiError> {
-        client::lists_get_all(&api_key, filter)
+        client::lists_get_all(&default_client(api_key), filter)
     }
 
     fn lists_get_by_id(api_key: String, list_id: u64) -> Result<ListSummary, ApiError> {
-        client::lists_get_by_id(&api_key, list_id)
+        client::lists_get_by_id(&default_client(api_key), list_id)
     }
 
     fn lists_get_leads(
@@ -70,11 +113,11 @@ impl Guest for Component {
         limit: u32,
         keyword: Option<String>,
     ) -> Result<ListLeadsPage, ApiError> {
-        client::lists_get_leads(&api_key, list_id, offset, limit, keyword)
+        client::lists_get_leads(&default_client(api_key), list_id, offset, limit, keyword)
     }
 
     fn lists_add_leads(api_key: String, list_id: u64, leads: Vec<Lead>) -> Result<(), ApiError> {
-        client::lists_add_leads(&api_key, list_id, leads)
+        client::lists_add_leads(&default_client(api_key), list_id, leads)
     }
 
     fn lists_add_leads_v2(
@@ -82,41 +125,41 @@ impl Guest for Component {
         list_id: u64,
         leads: Vec<Lead>,
     ) -> Result<CampaignAddLeadsV2Result, ApiError> {
-        client::lists_add_leads_v2(&api_key, list_id, leads)
+        client::lists_add_leads_v2(&default_client(api_key), list_id, leads)
     }
 
     fn lists_delete_leads(api_key: String, request: ListLeadDeleteRequest) -> Result<(), ApiError> {
-        client::lists_delete_leads(&api_key, request)
+        client::lists_delete_leads(&default_client(api_key), request)
     }
 
     fn lists_delete_leads_by_profile_url(
         api_key: String,
         request: ListLeadDeleteByProfileUrlRequest,
     ) -> Result<ListLeadDeleteByProfileUrlResponse, ApiError> {
-        client::lists_delete_leads_by_profile_url(&api_key, request)
+        client::lists_delete_leads_by_profile_url(&default_client(api_key), request)
     }
 
     // -------- Lead & Tags --------
     fn lead_get(api_key: String, profile_url: String) -> Result<Lead, ApiError> {
-        client::lead_get(&api_key, profile_url)
+        client::lead_get(&default_client(api_key), profile_url)
     }
 
     fn lead_get_lists(
         api_key: String,
         request: LeadListsRequest,
     ) -> Result<LeadListsResponse, ApiError> {
-        client::lead_get_lists(&api_key, request)
+        client::lead_get_lists(&default_client(api_key), request)
     }
 
     fn lead_get_tags(api_key: String, profile_url: String) -> Result<LeadTagsResponse, ApiError> {
-        client::lead_get_tags(&api_key, profile_url)
+        client::lead_get_tags(&default_client(api_key), profile_url)
     }
 
     fn lead_replace_tags(
         api_key: String,
         request: LeadReplaceTagsRequest,
     ) -> Result<LeadReplaceTagsResponse, ApiError> {
-        client::lead_replace_tags(&api_key, request)
+        client::lead_replace_tags(&default_client(api_key), request)
     }
 
     // -------- Inbox --------
@@ -124,14 +167,14 @@ impl Guest for Component {
         api_key: String,
         request: InboxGetConversationsRequest,
     ) -> Result<InboxConversationPage, ApiError> {
-        client::inbox_get_conversations_v2(&api_key, request)
+        client::inbox_get_conversations_v2(&default_client(api_key), request)
     }
 
     fn inbox_send_message(
         api_key: String,
         request: InboxSendMessageRequest,
     ) -> Result<(), ApiError> {
-        client::inbox_send_message(&api_key, request)
+        client::inbox_send_message(&default_client(api_key), request)
     }
 
     // -------- LinkedIn Accounts --------
@@ -139,7 +182,7 @@ impl Guest for Component {
         api_key: String,
         filter: LiAccountFilter,
     ) -> Result<LiAccountPage, ApiError> {
-        client::li_account_get_all(&api_key, filter)
+        client::li_account_get_all(&default_client(api_key), filter)
     }
 
     // -------- Webhooks --------
@@ -147,21 +190,373 @@ impl Guest for Component {
         api_key: String,
         request: CreateWebhookRequest,
     ) -> Result<Webhook, ApiError> {
-        client::webhooks_create(&api_key, request)
+        client::webhooks_create(&default_client(api_key), request)
     }
 
     fn webhooks_get_by_id(api_key: String, webhook_id: u64) -> Result<Webhook, ApiError> {
-        client::webhooks_get_by_id(&api_key, webhook_id)
+        client::webhooks_get_by_id(&default_client(api_key), webhook_id)
     }
 
     fn webhooks_get_all(
         api_key: String,
         filter: GetWebhooksFilter,
     ) -> Result<WebhookPage, ApiError> {
-        client::webhooks_get_all(&api_key, filter)
+        client::webhooks_get_all(&default_client(api_key), filter)
     }
 
     fn webhooks_delete(api_key: String, webhook_id: u64) -> Result<(), ApiError> {
-        client::webhooks_delete(&api_key, webhook_id)
+        client::webhooks_delete(&default_client(api_key), webhook_id)
+    }
+
+    fn webhooks_parse_event(body: String) -> Result<WebhookEvent, ApiError> {
+        client::webhooks_parse_event(&body)
+    }
+}
+
+/// Implementation of the `client` resource.
+pub struct HeyReachClient {
+    http: HttpClient,
+}
+
+impl GuestClient for HeyReachClient {
+    fn new(api_key: String, config: ClientConfig) -> Result<HeyReachClient, ApiError> {
+        let config = config::resolve(config)?;
+        Ok(HeyReachClient {
+            http: HttpClient::new(api_key, config),
+        })
+    }
+
+    fn with_timeouts(&self, timeouts: Timeouts) -> Result<Client, ApiError> {
+        Ok(Client::new(HeyReachClient {
+            http: self.http.with_timeouts(timeouts)?,
+        }))
+    }
+
+    fn metrics(&self) -> ClientMetrics {
+        self.http.metrics()
+    }
+
+    // -------- Auth --------
+    fn check_api_key(&self) -> Result<(), ApiError> {
+        client::check_api_key(&self.http)
+    }
+
+    // -------- Campaigns --------
+    fn campaigns_get_all(&self, filter: CampaignFilter) -> Result<CampaignPage, ApiError> {
+        client::campaigns_get_all(&self.http, filter)
+    }
+
+    fn campaigns_get_all_pages(
+        &self,
+        filter: CampaignFilter,
+        max_items: Option<u32>,
+    ) -> Result<Vec<CampaignSummary>, ApiError> {
+        client::campaigns_get_all_pages(&self.http, filter, max_items)
+    }
+
+    fn campaigns_cursor(
+        &self,
+        filter: CampaignFilter,
+        position: Option<String>,
+    ) -> Result<CampaignCursor, ApiError> {
+        let cursor = HeyReachCampaignCursor::new(self.http.clone(), filter, position)?;
+        Ok(CampaignCursor::new(cursor))
+    }
+
+    fn campaigns_get_by_id(&self, campaign_id: u64) -> Result<CampaignSummary, ApiError> {
+        client::campaigns_get_by_id(&self.http, campaign_id)
+    }
+
+    fn campaigns_resume(&self, campaign_id: u64) -> Result<(), ApiError> {
+        client::campaigns_resume(&self.http, campaign_id)
+    }
+
+    fn campaigns_pause(&self, campaign_id: u64) -> Result<(), ApiError> {
+        client::campaigns_pause(&self.http, campaign_id)
+    }
+
+    fn campaigns_add_leads(&self, payload: CampaignAddLeadsRequest) -> Result<u32, ApiError> {
+        client::campaigns_add_leads(&self.http, payload)
+    }
+
+    fn campaigns_add_leads_v2(
+        &self,
+        payload: CampaignAddLeadsRequest,
+    ) -> Result<CampaignAddLeadsV2Result, ApiError> {
+        client::campaigns_add_leads_v2(&self.http, payload)
+    }
+
+    fn campaigns_get_leads(
+        &self,
+        request: CampaignLeadsRequest,
+    ) -> Result<CampaignLeadsPage, ApiError> {
+        client::campaigns_get_leads(&self.http, request)
+    }
+
+    fn campaigns_get_leads_all_pages(
+        &self,
+        request: CampaignLeadsRequest,
+        max_items: Option<u32>,
+    ) -> Result<Vec<CampaignLead>, ApiError> {
+        client::campaigns_get_leads_all_pages(&self.http, request, max_items)
+    }
+
+    fn campaigns_stop_lead(&self, request: StopLeadRequest) -> Result<(), ApiError> {
+        client::campaigns_stop_lead(&self.http, request)
+    }
+
+    // -------- Lists --------
+    fn lists_get_all(&self, filter: ListGetAllFilter) -> Result<ListPage, ApiError> {
+        client::lists_get_all(&self.http, filter)
+    }
+
+    fn lists_get_all_pages(
+        &self,
+        filter: ListGetAllFilter,
+        max_items: Option<u32>,
+    ) -> Result<Vec<ListSummary>, ApiError> {
+        client::lists_get_all_pages(&self.http, filter, max_items)
+    }
+
+    fn lists_get_by_id(&self, list_id: u64) -> Result<ListSummary, ApiError> {
+        client::lists_get_by_id(&self.http, list_id)
+    }
+
+    fn lists_get_leads(
+        &self,
+        list_id: u64,
+        offset: u32,
+        limit: u32,
+        keyword: Option<String>,
+    ) -> Result<ListLeadsPage, ApiError> {
+        client::lists_get_leads(&self.http, list_id, offset, limit, keyword)
+    }
+
+    fn lists_get_leads_all_pages(
+        &self,
+        list_id: u64,
+        keyword: Option<String>,
+        max_items: Option<u32>,
+    ) -> Result<Vec<Lead>, ApiError> {
+        client::lists_get_leads_all_pages(&self.http, list_id, keyword, max_items)
+    }
+
+    fn lists_leads_cursor(
+        &self,
+        list_id: u64,
+        keyword: Option<String>,
+        page_size: Option<u32>,
+        position: Option<String>,
+    ) -> Result<ListLeadsCursor, ApiError> {
+        let cursor =
+            HeyReachListLeadsCursor::new(self.http.clone(), list_id, keyword, page_size, position)?;
+        Ok(ListLeadsCursor::new(cursor))
+    }
+
+    fn lists_create_empty(&self, request: CreateListRequest) -> Result<ListSummary, ApiError> {
+        client::lists_create_empty(&self.http, request)
+    }
+
+    fn lists_get_companies(
+        &self,
+        list_id: u64,
+        offset: u32,
+        limit: u32,
+        keyword: Option<String>,
+    ) -> Result<CompanyPage, ApiError> {
+        client::lists_get_companies(&self.http, list_id, offset, limit, keyword)
+    }
+
+    fn lists_get_companies_all_pages(
+        &self,
+        list_id: u64,
+        keyword: Option<String>,
+        max_items: Option<u32>,
+    ) -> Result<Vec<Company>, ApiError> {
+        client::lists_get_companies_all_pages(&self.http, list_id, keyword, max_items)
+    }
+
+    fn lists_add_leads(&self, list_id: u64, leads: Vec<Lead>) -> Result<(), ApiError> {
+        client::lists_add_leads(&self.http, list_id, leads)
+    }
+
+    fn lists_add_leads_v2(
+        &self,
+        list_id: u64,
+        leads: Vec<Lead>,
+    ) -> Result<CampaignAddLeadsV2Result, ApiError> {
+        client::lists_add_leads_v2(&self.http, list_id, leads)
+    }
+
+    fn lists_delete_leads(&self, request: ListLeadDeleteRequest) -> Result<(), ApiError> {
+        client::lists_delete_leads(&self.http, request)
+    }
+
+    fn lists_delete_leads_by_profile_url(
+        &self,
+        request: ListLeadDeleteByProfileUrlRequest,
+    ) -> Result<ListLeadDeleteByProfileUrlResponse, ApiError> {
+        client::lists_delete_leads_by_profile_url(&self.http, request)
+    }
+
+    // -------- Lead & Tags --------
+    fn lead_get(&self, profile_url: String) -> Result<Lead, ApiError> {
+        client::lead_get(&self.http, profile_url)
+    }
+
+    fn lead_get_lists(&self, request: LeadListsRequest) -> Result<LeadListsResponse, ApiError> {
+        client::lead_get_lists(&self.http, request)
+    }
+
+    fn lead_get_lists_all_pages(
+        &self,
+        request: LeadListsRequest,
+        max_items: Option<u32>,
+    ) -> Result<Vec<LeadListSummary>, ApiError> {
+        client::lead_get_lists_all_pages(&self.http, request, max_items)
+    }
+
+    fn lead_get_campaigns(
+        &self,
+        request: LeadCampaignsRequest,
+    ) -> Result<LeadCampaignsResponse, ApiError> {
+        client::lead_get_campaigns(&self.http, request)
+    }
+
+    fn lead_get_campaigns_all_pages(
+        &self,
+        request: LeadCampaignsRequest,
+        max_items: Option<u32>,
+    ) -> Result<Vec<LeadCampaignSummary>, ApiError> {
+        client::lead_get_campaigns_all_pages(&self.http, request, max_items)
+    }
+
+    fn lead_get_tags(&self, profile_url: String) -> Result<LeadTagsResponse, ApiError> {
+        client::lead_get_tags(&self.http, profile_url)
+    }
+
+    fn lead_replace_tags(
+        &self,
+        request: LeadReplaceTagsRequest,
+    ) -> Result<LeadReplaceTagsResponse, ApiError> {
+        client::lead_replace_tags(&self.http, request)
+    }
+
+    fn lead_add_tags(&self, request: LeadAddTagsRequest) -> Result<LeadAddTagsResponse, ApiError> {
+        client::lead_add_tags(&self.http, request)
+    }
+
+    fn lead_remove_tags(&self, request: LeadRemoveTagsRequest) -> Result<(), ApiError> {
+        client::lead_remove_tags(&self.http, request)
+    }
+
+    fn tags_get_all(&self, max_items: Option<u32>) -> Result<Vec<String>, ApiError> {
+        client::tags_get_all(&self.http, max_items)
+    }
+
+    // -------- Inbox --------
+    fn inbox_get_conversations_v2(
+        &self,
+        request: InboxGetConversationsRequest,
+    ) -> Result<InboxConversationPage, ApiError> {
+        client::inbox_get_conversations_v2(&self.http, request)
+    }
+
+    fn inbox_get_conversations_v2_all_pages(
+        &self,
+        request: InboxGetConversationsRequest,
+        max_items: Option<u32>,
+    ) -> Result<Vec<InboxConversationSummary>, ApiError> {
+        client::inbox_get_conversations_v2_all_pages(&self.http, request, max_items)
+    }
+
+    fn inbox_get_conversation(
+        &self,
+        linked_in_account_id: u32,
+        conversation_id: String,
+    ) -> Result<ConversationThread, ApiError> {
+        client::inbox_get_conversation(&self.http, linked_in_account_id, conversation_id)
+    }
+
+    fn inbox_send_message(&self, request: InboxSendMessageRequest) -> Result<(), ApiError> {
+        client::inbox_send_message(&self.http, request)
+    }
+
+    // -------- LinkedIn Accounts --------
+    fn li_account_get_all(&self, filter: LiAccountFilter) -> Result<LiAccountPage, ApiError> {
+        client::li_account_get_all(&self.http, filter)
+    }
+
+    fn li_account_get_all_pages(
+        &self,
+        filter: LiAccountFilter,
+        max_items: Option<u32>,
+    ) -> Result<Vec<LiAccountSummary>, ApiError> {
+        client::li_account_get_all_pages(&self.http, filter, max_items)
+    }
+
+    fn li_account_get_by_id(&self, linked_in_account_id: u32) -> Result<LiAccountDetail, ApiError> {
+        client::li_account_get_by_id(&self.http, linked_in_account_id)
+    }
+
+    fn li_account_get_network(
+        &self,
+        request: MyNetworkRequest,
+    ) -> Result<NetworkConnectionPage, ApiError> {
+        client::li_account_get_network(&self.http, request)
+    }
+
+    fn li_account_get_network_all_pages(
+        &self,
+        linked_in_account_id: u32,
+        max_items: Option<u32>,
+    ) -> Result<Vec<NetworkConnection>, ApiError> {
+        client::li_account_get_network_all_pages(&self.http, linked_in_account_id, max_items)
+    }
+
+    // -------- Webhooks --------
+    fn webhooks_create(&self, request: CreateWebhookRequest) -> Result<Webhook, ApiError> {
+        client::webhooks_create(&self.http, request)
+    }
+
+    fn webhooks_get_by_id(&self, webhook_id: u64) -> Result<Webhook, ApiError> {
+        client::webhooks_get_by_id(&self.http, webhook_id)
+    }
+
+    fn webhooks_get_all(&self, filter: GetWebhooksFilter) -> Result<WebhookPage, ApiError> {
+        client::webhooks_get_all(&self.http, filter)
+    }
+
+    fn webhooks_get_all_pages(
+        &self,
+        filter: GetWebhooksFilter,
+        max_items: Option<u32>,
+    ) -> Result<Vec<Webhook>, ApiError> {
+        client::webhooks_get_all_pages(&self.http, filter, max_items)
+    }
+
+    fn webhooks_update(
+        &self,
+        webhook_id: u64,
+        request: UpdateWebhookRequest,
+    ) -> Result<Option<Webhook>, ApiError> {
+        client::webhooks_update(&self.http, webhook_id, request)
+    }
+
+    fn webhooks_delete(&self, webhook_id: u64) -> Result<(), ApiError> {
+        client::webhooks_delete(&self.http, webhook_id)
+    }
+
+    fn webhooks_ensure(
+        &self,
+        desired: Vec<CreateWebhookRequest>,
+        options: WebhookEnsureOptions,
+    ) -> Result<WebhookReconcileResult, ApiError> {
+        client::webhooks_ensure(&self.http, desired, options)
+    }
+
+    // -------- Stats --------
+    fn stats_get_overall(&self, filter: StatsFilter) -> Result<CampaignStats, ApiError> {
+        client::stats_get_overall(&self.http, filter)
     }
 }
@@ -0,0 +1,214 @@
+//! Levelled, structured logging.
+//!
+//! Lines go to `wasi:logging` inside the component and to stderr on native
+//! targets. Every line is a message followed by `key=value` fields and passes
+//! through `redact` before it is emitted.
+
+use crate::exports::heyreach::client::api::LogLevel;
+use std::fmt::Display;
+
+const CONTEXT: &str = "heyreach";
+
+/// Headers whose values are never logged.
+const SENSITIVE_HEADERS: &[&str] = &["x-api-key", "authorization", "cookie", "set-cookie"];
+
+/// Emits log lines at or above a configured level.
+#[derive(Debug, Clone, Copy)]
+pub struct Logger {
+    max: LogLevel,
+}
+
+impl Logger {
+    pub fn new(max: LogLevel) -> Self {
+        Logger { max }
+    }
+
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        !matches!(level, LogLevel::Off) && rank(level) >= rank(self.max)
+    }
+
+    pub fn trace(&self, message: &str, fields: &[(&str, &dyn Display)]) {
+        self.log(LogLevel::Trace, message, fields);
+    }
+
+    pub fn debug(&self, message: &str, fields: &[(&str, &dyn Display)]) {
+        self.log(LogLevel::Debug, message, fields);
+    }
+
+    pub fn info(&self, message: &str, fields: &[(&str, &dyn Display)]) {
+        self.log(LogLevel::Info, message, fields);
+    }
+
+    pub fn warn(&self, message: &str, fields: &[(&str, &dyn Display)]) {
+        self.log(LogLevel::Warn, message, fields);
+    }
+
+    pub fn error(&self, message: &str, fields: &[(&str, &dyn Display)]) {
+        self.log(LogLevel::Error, message, fields);
+    }
+
+    pub fn log(&self, level: LogLevel, message: &str, fields: &[(&str, &dyn Display)]) {
+        if !self.enabled(level) {
+            return;
+        }
+
+        let mut line = message.to_string();
+        for (key, value) in fields {
+            let value = value.to_string();
+            if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"') {
+                line.push_str(&format!(" {}={:?}", key, value));
+            } else {
+                line.push_str(&format!(" {}={}", key, value));
+            }
+        }
+
+        emit(level, &redact(&line));
+    }
+}
+
+fn rank(level: LogLevel) -> u8 {
+    match level {
+        LogLevel::Trace => 0,
+        LogLevel::Debug => 1,
+        LogLevel::Info => 2,
+        LogLevel::Warn => 3,
+        LogLevel::Error => 4,
+        LogLevel::Off => 5,
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn emit(level: LogLevel, line: &str) {
+    use crate::wasi::logging::logging::{self, Level};
+
+    let level = match level {
+        LogLevel::Trace => Level::Trace,
+        LogLevel::Debug => Level::Debug,
+        LogLevel::Info => Level::Info,
+        LogLevel::Warn => Level::Warn,
+        LogLevel::Error | LogLevel::Off => Level::Error,
+    };
+    logging::log(level, CONTEXT, line);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn emit(level: LogLevel, line: &str) {
+    let level = match level {
+        LogLevel::Trace => "TRACE",
+        LogLevel::Debug => "DEBUG",
+        LogLevel::Info => "INFO",
+        LogLevel::Warn => "WARN",
+        LogLevel::Error | LogLevel::Off => "ERROR",
+    };
+    eprintln!("[{}] {}: {}", level, CONTEXT, line);
+}
+
+/// Renders headers as `name: value` pairs with sensitive values replaced.
+pub fn headers(headers: &[(String, Vec<u8>)]) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let sensitive = SENSITIVE_HEADERS
+                .iter()
+                .any(|s| name.eq_ignore_ascii_case(s));
+            if sensitive {
+                format!("{}: [redacted]", name)
+            } else {
+                format!("{}: {}", name, String::from_utf8_lossy(value))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Replaces anything that looks like an email address with `[email]`.
+pub fn redact(text: &str) -> String {
+    let is_local = |c: char| c.is_ascii_alphanumeric() || "._%+-".contains(c);
+    let is_domain = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '-';
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(at) = rest.find('@') {
+        let local_start = rest[..at]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, c)| is_local(c))
+            .last()
+            .map(|(i, _)| i)
+            .unwrap_or(at);
+        let domain_len = rest[at + 1..]
+            .find(|c: char| !is_domain(c))
+            .unwrap_or(rest.len() - at - 1);
+        let domain = rest[at + 1..at + 1 + domain_len].trim_end_matches('.');
+
+        if local_start < at && domain.contains('.') && !domain.starts_with('.') {
+            out.push_str(&rest[..local_start]);
+            out.push_str("[email]");
+            rest = &rest[at + 1 + domain.len()..];
+        } else {
+            out.push_str(&rest[..=at]);
+            rest = &rest[at + 1..];
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_emails_anywhere_in_a_line() {
+        assert_eq!(
+            redact("lead added email=jane.doe+q2@mail.example.com status=200"),
+            "lead added email=[email] status=200"
+        );
+        assert_eq!(redact("a@b.io, c_d@e-f.co.uk."), "[email], [email].");
+        assert_eq!(redact("jane@example.com"), "[email]");
+    }
+
+    #[test]
+    fn leaves_at_signs_without_an_email() {
+        for text in [
+            "@",
+            "handle=@heyreach",
+            "user@localhost",
+            "user@.com",
+            "a @ b.com",
+            "price@ 5.00",
+            "trailing@",
+        ] {
+            assert_eq!(redact(text), text);
+        }
+    }
+
+    #[test]
+    fn handles_multibyte_characters_next_to_at() {
+        assert_eq!(redact("ü@example.com"), "ü@example.com");
+        assert_eq!(redact("jane@ü.com"), "jane@ü.com");
+        assert_eq!(redact("→jane@example.com←"), "→[email]←");
+        assert_eq!(redact("名前 jane@example.com 名前"), "名前 [email] 名前");
+        assert_eq!(redact("ñ@ñ"), "ñ@ñ");
+    }
+
+    #[test]
+    fn sensitive_headers_are_redacted_in_any_case() {
+        let rendered = headers(&[
+            ("X-API-Key".to_string(), b"secret-1".to_vec()),
+            ("x-api-key".to_string(), b"secret-2".to_vec()),
+            ("AUTHORIZATION".to_string(), b"Bearer secret-3".to_vec()),
+            ("Set-Cookie".to_string(), b"session=secret-4".to_vec()),
+            ("content-type".to_string(), b"application/json".to_vec()),
+        ]);
+
+        assert!(!rendered.contains("secret"));
+        assert_eq!(
+            rendered,
+            "X-API-Key: [redacted], x-api-key: [redacted], AUTHORIZATION: [redacted], \
+             Set-Cookie: [redacted], content-type: application/json"
+        );
+    }
+}
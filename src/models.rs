@@ -136,6 +136,42 @@ pub struct CampaignAddLeadsV2ResultDto {
     pub failed_leads_count: u32,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignLeadsRequestDto {
+    pub campaign_id: u64,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignLeadDto {
+    #[serde(rename = "linkedInUserProfile", alias = "lead")]
+    pub lead: LeadDto,
+    #[serde(default)]
+    pub lead_campaign_status: String,
+    #[serde(default, rename = "linkedInSenderId")]
+    pub linked_in_sender_id: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CampaignLeadsPageDto {
+    pub total_count: u32,
+    pub items: Vec<CampaignLeadDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StopLeadRequestDto {
+    pub campaign_id: u64,
+    #[serde(rename = "leadUrl", skip_serializing_if = "Option::is_none")]
+    pub lead_profile_url: Option<String>,
+    #[serde(rename = "leadLinkedInId", skip_serializing_if = "Option::is_none")]
+    pub lead_linked_in_id: Option<String>,
+}
+
 // -------- Lists --------
 
 #[derive(Debug, Serialize)]
@@ -184,6 +220,47 @@ pub struct ListGetLeadsRequestDto {
     pub keyword: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateEmptyListRequestDto {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub list_type: String,
+}
+
+/// `CreateEmptyList` response. A new list has no items or campaigns, which
+/// the API may leave out.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedListDto {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub total_items_count: u32,
+    pub list_type: String,
+    pub creation_time: String,
+    #[serde(default)]
+    pub campaign_ids: Vec<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanyDto {
+    pub name: String,
+    #[serde(rename = "linkedInUrl", alias = "profileUrl")]
+    pub linked_in_url: Option<String>,
+    pub industry: Option<String>,
+    pub company_size: Option<String>,
+    pub location: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanyPageDto {
+    pub total_count: u32,
+    pub items: Vec<CompanyDto>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListAddLeadsRequestDto {
@@ -219,9 +296,11 @@ pub struct LeadGetRequestDto {
     pub profile_url: String,
 }
 
+/// Identifies a lead by email, LinkedIn id or profile URL, with paging.
+/// Shared by `GetListsForLead` and `GetCampaignsForLead`.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct LeadListsRequestDto {
+pub struct LeadLookupRequestDto {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -247,6 +326,24 @@ pub struct LeadListsResponseDto {
     pub items: Vec<LeadListSummaryDto>,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeadCampaignSummaryDto {
+    pub campaign_id: u64,
+    pub campaign_name: String,
+    #[serde(default)]
+    pub campaign_status: String,
+    #[serde(default)]
+    pub lead_campaign_status: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeadCampaignsResponseDto {
+    pub total_count: u32,
+    pub items: Vec<LeadCampaignSummaryDto>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LeadTagsResponseDto {
     pub tags: Vec<String>,
@@ -269,6 +366,53 @@ pub struct LeadReplaceTagsResponseDto {
     pub new_assigned_tags: Vec<String>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeadAddTagsRequestDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lead_profile_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lead_linked_in_id: Option<String>,
+    pub tags: Vec<String>,
+    pub create_tag_if_not_existing: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeadAddTagsResponseDto {
+    pub new_assigned_tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LeadRemoveTagsRequestDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lead_profile_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lead_linked_in_id: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagsGetAllRequestDto {
+    pub offset: u32,
+    pub limit: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagDto {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagPageDto {
+    pub total_count: u32,
+    pub items: Vec<TagDto>,
+}
+
 // -------- Inbox --------
 
 #[derive(Debug, Serialize)]
@@ -297,7 +441,7 @@ pub struct InboxGetConversationsRequestDto {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InboxConversationSummaryDto {
-    #[serde(rename = "id")]  
+    #[serde(rename = "id")]
     pub conversation_id: String,
     pub linked_in_account_id: u32,
     pub lead_profile_url: Option<String>,
@@ -320,11 +464,41 @@ pub struct InboxSendMessageRequestDto {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subject: Option<String>,
-    #[serde(rename = "id")]  
+    #[serde(rename = "id")]
     pub conversation_id: String,
     pub linked_in_account_id: u32,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatroomMessageDto {
+    pub created_at: String,
+    #[serde(default)]
+    pub body: String,
+    pub subject: Option<String>,
+    #[serde(default)]
+    pub is_in_mail: bool,
+    #[serde(default)]
+    pub sender: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrespondentProfileDto {
+    pub profile_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatroomDto {
+    #[serde(rename = "id")]
+    pub conversation_id: String,
+    pub linked_in_account_id: u32,
+    pub correspondent_profile: Option<CorrespondentProfileDto>,
+    #[serde(default)]
+    pub messages: Vec<ChatroomMessageDto>,
+}
+
 // -------- LinkedIn Accounts --------
 
 #[derive(Debug, Serialize)]
@@ -355,6 +529,32 @@ pub struct LiAccountSummaryDto {
     pub is_valid_recruiter: bool,
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LiAccountDailyLimitsDto {
+    pub connection_requests: Option<u32>,
+    pub messages: Option<u32>,
+    #[serde(rename = "inMails")]
+    pub inmails: Option<u32>,
+    pub profile_views: Option<u32>,
+    pub follows: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiAccountDetailDto {
+    #[serde(flatten)]
+    pub summary: LiAccountSummaryDto,
+    pub profile_url: Option<String>,
+    pub subscription_type: Option<String>,
+    #[serde(default)]
+    pub daily_limits: LiAccountDailyLimitsDto,
+    pub proxy_type: Option<String>,
+    pub proxy_location: Option<String>,
+    pub session_status: Option<String>,
+    #[serde(default)]
+    pub campaign_ids: Vec<u64>,
+}
 
 // ✅ FIXED: API returns {totalCount, items}, NOT {page, items}
 #[derive(Debug, Deserialize)]
@@ -364,6 +564,34 @@ pub struct LiAccountPageDto {
     pub items: Vec<LiAccountSummaryDto>,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MyNetworkRequestDto {
+    pub sender_id: u32,
+    pub page_number: u32,
+    pub page_size: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConnectionDto {
+    pub profile_url: String,
+    #[serde(default)]
+    pub first_name: String,
+    #[serde(default)]
+    pub last_name: String,
+    pub headline: Option<String>,
+    #[serde(alias = "connectionDate")]
+    pub connected_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkConnectionPageDto {
+    pub total_count: u32,
+    pub items: Vec<NetworkConnectionDto>,
+}
+
 // -------- Webhooks --------
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -387,6 +615,21 @@ pub struct CreateWebhookRequestDto {
     pub is_active: bool,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateWebhookRequestDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub campaign_ids: Option<Vec<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GetWebhooksFilterDto {
@@ -401,3 +644,129 @@ pub struct WebhookPageDto {
     pub total_count: u32, // ✅ Direct field from API response
     pub items: Vec<WebhookDto>,
 }
+
+// -------- Webhook events --------
+
+// Webhook deliveries use snake_case; the camelCase aliases cover payloads
+// relayed through tools that re-serialize them.
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookEventDto {
+    /// String or number depending on the sender.
+    #[serde(default, alias = "eventId", alias = "id")]
+    pub event_id: Option<serde_json::Value>,
+    #[serde(alias = "eventType")]
+    pub event_type: String,
+    pub timestamp: String,
+    pub lead: Option<WebhookLeadDto>,
+    pub sender: Option<WebhookSenderDto>,
+    pub campaign: Option<WebhookCampaignDto>,
+    #[serde(default, alias = "conversationId")]
+    pub conversation_id: Option<String>,
+    #[serde(default, alias = "recentMessages")]
+    pub recent_messages: Option<Vec<WebhookMessageDto>>,
+    #[serde(default, alias = "isInmail", alias = "isInMail")]
+    pub is_inmail: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookLeadDto {
+    /// String or number depending on the event.
+    #[serde(default)]
+    pub id: Option<serde_json::Value>,
+    #[serde(default, alias = "profileUrl")]
+    pub profile_url: Option<String>,
+    #[serde(default, alias = "firstName")]
+    pub first_name: Option<String>,
+    #[serde(default, alias = "lastName")]
+    pub last_name: Option<String>,
+    #[serde(default, alias = "fullName")]
+    pub full_name: Option<String>,
+    #[serde(default, alias = "companyName")]
+    pub company_name: Option<String>,
+    #[serde(default)]
+    pub position: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default, alias = "emailAddress")]
+    pub email_address: Option<String>,
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookSenderDto {
+    #[serde(default)]
+    pub id: Option<u32>,
+    #[serde(default, alias = "firstName")]
+    pub first_name: Option<String>,
+    #[serde(default, alias = "lastName")]
+    pub last_name: Option<String>,
+    #[serde(default, alias = "fullName")]
+    pub full_name: Option<String>,
+    #[serde(default, alias = "emailAddress")]
+    pub email_address: Option<String>,
+    #[serde(default, alias = "profileUrl")]
+    pub profile_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookCampaignDto {
+    #[serde(default)]
+    pub id: Option<u64>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookMessageDto {
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default, alias = "creationTime")]
+    pub creation_time: Option<String>,
+    #[serde(default, alias = "isReply")]
+    pub is_reply: Option<bool>,
+}
+
+// -------- Stats --------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsFilterDto {
+    pub account_ids: Vec<u32>,
+    pub campaign_ids: Vec<u64>,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+// Counters are required so a changed response shape fails to decode instead
+// of reading as zeros. Rates are derived from them and default to 0.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverallStatsDto {
+    pub connections_sent: u32,
+    pub connections_accepted: u32,
+    #[serde(default)]
+    pub connection_acceptance_rate: f64,
+    pub messages_sent: u32,
+    pub total_message_started: u32,
+    pub total_message_replies: u32,
+    #[serde(default)]
+    pub message_reply_rate: f64,
+    pub inmail_messages_sent: u32,
+    pub total_inmail_started: u32,
+    pub total_inmail_replies: u32,
+    #[serde(default, rename = "inMailReplyRate")]
+    pub inmail_reply_rate: f64,
+    pub profile_views: u32,
+    pub post_likes: u32,
+    pub follows: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatsResponseDto {
+    pub overall_stats: OverallStatsDto,
+}
@@ -0,0 +1,206 @@
+use crate::clock;
+use crate::exports::heyreach::client::api::{ApiError, ApiErrorCode, RateLimit, RateLimitMode};
+use crate::http::api_error;
+use crate::log::Logger;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// HeyReach allows 300 requests per minute per API key.
+pub fn default_rate_limit() -> RateLimit {
+    RateLimit {
+        requests_per_minute: 300,
+        burst: None,
+        mode: RateLimitMode::Block,
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill_ns: u64,
+}
+
+impl TokenBucket {
+    fn full(limit: &RateLimit, now_ns: u64) -> Self {
+        TokenBucket {
+            tokens: capacity(limit),
+            last_refill_ns: now_ns,
+        }
+    }
+
+    /// Refills for the time since the last call and takes one token. Returns
+    /// 0 on success, or how many milliseconds until a token is available.
+    fn take(&mut self, limit: &RateLimit, now_ns: u64) -> u64 {
+        let tokens_per_ns = limit.requests_per_minute as f64 / 60_000_000_000.0;
+        let elapsed = now_ns.saturating_sub(self.last_refill_ns) as f64;
+        self.tokens = (self.tokens + elapsed * tokens_per_ns).min(capacity(limit));
+        self.last_refill_ns = now_ns;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            0
+        } else {
+            ((1.0 - self.tokens) / tokens_per_ns / 1_000_000.0).ceil() as u64
+        }
+    }
+}
+
+fn capacity(limit: &RateLimit) -> f64 {
+    limit.burst.unwrap_or(limit.requests_per_minute).max(1) as f64
+}
+
+thread_local! {
+    // Keyed by API key so every client and free function sharing a key
+    // draws from the same budget. This lives in the component instance, so
+    // it is not shared across instances: on hosts that start an instance per
+    // invocation (e.g. wasmCloud) each invocation starts with a full bucket.
+    static BUCKETS: RefCell<HashMap<String, TokenBucket>> = RefCell::new(HashMap::new());
+}
+
+/// Takes one token from the bucket for `api_key`, waiting for it or failing
+/// with `rate-limited` depending on `limit.mode`.
+pub fn acquire(api_key: &str, limit: &RateLimit, log: &Logger) -> Result<(), ApiError> {
+    if limit.requests_per_minute == 0 {
+        return Ok(());
+    }
+
+    loop {
+        let wait_ms = BUCKETS.with(|buckets| {
+            let now = clock::now_ns();
+            buckets
+                .borrow_mut()
+                .entry(api_key.to_string())
+                .or_insert_with(|| TokenBucket::full(limit, now))
+                .take(limit, now)
+        });
+
+        if wait_ms == 0 {
+            return Ok(());
+        }
+
+        match limit.mode {
+            RateLimitMode::Block => {
+                log.info(
+                    "client-side rate limit reached, waiting",
+                    &[("wait_ms", &wait_ms)],
+                );
+                clock::sleep_ms(wait_ms);
+            }
+            RateLimitMode::FailFast => {
+                return Err(api_error(
+                    ApiErrorCode::RateLimited,
+                    &format!(
+                        "Client-side rate limit of {} requests/minute reached; next slot in {} ms",
+                        limit.requests_per_minute, wait_ms
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exports::heyreach::client::api::LogLevel;
+
+    const SECOND_NS: u64 = 1_000_000_000;
+
+    fn limit(requests_per_minute: u32, burst: Option<u32>, mode: RateLimitMode) -> RateLimit {
+        RateLimit {
+            requests_per_minute,
+            burst,
+            mode,
+        }
+    }
+
+    #[test]
+    fn burst_allows_back_to_back_requests() {
+        let limit = limit(60, Some(3), RateLimitMode::Block);
+        let mut bucket = TokenBucket::full(&limit, 0);
+
+        assert_eq!(bucket.take(&limit, 0), 0);
+        assert_eq!(bucket.take(&limit, 0), 0);
+        assert_eq!(bucket.take(&limit, 0), 0);
+        assert_eq!(bucket.take(&limit, 0), 1_000);
+    }
+
+    #[test]
+    fn burst_defaults_to_requests_per_minute() {
+        let limit = limit(5, None, RateLimitMode::Block);
+        let mut bucket = TokenBucket::full(&limit, 0);
+
+        for _ in 0..5 {
+            assert_eq!(bucket.take(&limit, 0), 0);
+        }
+        assert_eq!(bucket.take(&limit, 0), 12_000);
+    }
+
+    #[test]
+    fn tokens_refill_over_time_up_to_capacity() {
+        let limit = limit(60, Some(2), RateLimitMode::Block);
+        let mut bucket = TokenBucket::full(&limit, 0);
+        bucket.take(&limit, 0);
+        bucket.take(&limit, 0);
+
+        // Half a token after 500 ms.
+        assert_eq!(bucket.take(&limit, SECOND_NS / 2), 500);
+        assert_eq!(bucket.take(&limit, SECOND_NS), 0);
+
+        // A long idle period refills only to the burst size.
+        assert_eq!(bucket.take(&limit, 3_600 * SECOND_NS), 0);
+        assert_eq!(bucket.take(&limit, 3_600 * SECOND_NS), 0);
+        assert_eq!(bucket.take(&limit, 3_600 * SECOND_NS), 1_000);
+    }
+
+    #[test]
+    fn fail_fast_returns_rate_limited() {
+        let log = Logger::new(LogLevel::Off);
+        let limit = limit(1, Some(1), RateLimitMode::FailFast);
+
+        acquire("fail-fast-key", &limit, &log).unwrap();
+        let error = acquire("fail-fast-key", &limit, &log).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::RateLimited);
+    }
+
+    #[test]
+    fn zero_requests_per_minute_disables_limit() {
+        let log = Logger::new(LogLevel::Off);
+        let limit = limit(0, Some(1), RateLimitMode::FailFast);
+
+        for _ in 0..10 {
+            acquire("unlimited-key", &limit, &log).unwrap();
+        }
+    }
+
+    #[test]
+    fn clients_with_one_key_share_a_bucket() {
+        use crate::config::HttpConfig;
+        use crate::http::{HttpClient, HttpMethod};
+        use crate::retry::RetryMode;
+        use crate::transport::MemoryTransport;
+        use std::rc::Rc;
+
+        let transport = Rc::new(MemoryTransport::new());
+        let client = |api_key: &str| {
+            let config = HttpConfig {
+                rate_limit: limit(1, Some(1), RateLimitMode::FailFast),
+                log_level: LogLevel::Off,
+                ..HttpConfig::default()
+            };
+            HttpClient::with_transport(api_key.to_string(), config, transport.clone())
+        };
+        let get = |http: &HttpClient| {
+            http.make_request_empty(HttpMethod::Get, "/ping", RetryMode::Read, None::<&()>)
+        };
+
+        transport.push_json(200, "");
+        transport.push_json(200, "");
+        get(&client("shared-key")).unwrap();
+
+        let error = get(&client("shared-key")).unwrap_err();
+        assert_eq!(error.code, ApiErrorCode::RateLimited);
+
+        get(&client("other-key")).unwrap();
+        assert_eq!(transport.requests().len(), 2);
+    }
+}
@@ -0,0 +1,214 @@
+//! `wasi:http/incoming-handler` for HeyReach webhook deliveries.
+//!
+//! Accepts `POST` requests carrying a HeyReach event, checks that they are
+//! authentic and fresh (see `verify.rs`), parses the body with
+//! `client::webhooks_parse_event` and passes the event to the imported
+//! `webhook-handler`. Rejected deliveries get a 4xx with a plain-text reason,
+//! which HeyReach shows in its delivery log.
+
+use crate::exports::wasi::http::incoming_handler::Guest;
+use crate::heyreach::client::webhook_events::WebhookEvent;
+use crate::heyreach::client::webhook_handler;
+use crate::log::Logger;
+use crate::verify::{self, WebhookSecurity};
+use crate::wasi::http::types::*;
+use crate::wasi::io::streams::StreamError;
+use crate::{client, clock, config, Component};
+
+/// Real deliveries are a few KiB; anything past this is answered with 413.
+const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// `blocking-write-and-flush` accepts at most 4096 bytes per call.
+const MAX_REASON_BYTES: usize = 1024;
+
+/// Why a delivery was not accepted, sent back as the response.
+struct Rejection {
+    status: u16,
+    reason: String,
+}
+
+impl Rejection {
+    fn new(status: u16, reason: &str) -> Self {
+        Rejection {
+            status,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+impl Guest for Component {
+    fn handle(request: IncomingRequest, response_out: ResponseOutparam) {
+        let log = Logger::new(config::current().log_level);
+
+        match receive(request, &log) {
+            Ok(message) => respond(response_out, 200, message),
+            Err(rejection) => respond(response_out, rejection.status, &rejection.reason),
+        }
+    }
+}
+
+/// Handles one delivery; on success returns the response body.
+fn receive(request: IncomingRequest, log: &Logger) -> Result<&'static str, Rejection> {
+    if !matches!(request.method(), Method::Post) {
+        return Err(Rejection::new(405, "Webhook deliveries must use POST"));
+    }
+
+    let security = WebhookSecurity::load().map_err(|e| {
+        log.error("webhook verification is not configured", &[("error", &e)]);
+        Rejection::new(500, "Webhook verification is not configured")
+    })?;
+
+    let headers = {
+        let fields = request.headers();
+        let entries = fields.entries();
+        drop(fields);
+        entries
+    };
+    let path_with_query = request.path_with_query();
+
+    let body = read_body(request)?;
+
+    security
+        .authenticate(&headers, path_with_query.as_deref(), &body)
+        .map_err(|reason| {
+            log.warn("rejected unauthenticated webhook", &[("reason", &reason)]);
+            Rejection::new(401, reason)
+        })?;
+
+    let event = parse_body(&body).inspect_err(|rejection| {
+        log.warn("rejected webhook delivery", &[("error", &rejection.reason)]);
+    })?;
+
+    let now_ms = clock::unix_now_ms();
+    security
+        .check_timestamp(&event.timestamp, now_ms)
+        .map_err(|reason| {
+            log.warn("rejected stale webhook", &[("reason", &reason)]);
+            Rejection::new(401, &reason)
+        })?;
+
+    let replay_key = verify::replay_key(event.event_id.as_deref(), &body);
+    let seen = security.already_seen(&replay_key, now_ms).map_err(|e| {
+        log.error("webhook dedupe store failed", &[("error", &e)]);
+        Rejection::new(500, "Webhook dedupe store is unavailable")
+    })?;
+    if seen {
+        log.info(
+            "ignored duplicate webhook event",
+            &[("event_id", &event.event_id.as_deref().unwrap_or("-"))],
+        );
+        return Ok("Duplicate event ignored");
+    }
+
+    log.debug(
+        "received webhook event",
+        &[
+            ("event_type", &format!("{:?}", event.event_type)),
+            ("timestamp", &event.timestamp),
+        ],
+    );
+
+    webhook_handler::handle_event(&event).map_err(|e| {
+        log.error("webhook handler failed", &[("error", &e)]);
+        Rejection::new(500, &format!("Webhook handler failed: {}", e))
+    })?;
+
+    // The event was handled, so a failed write must not ask for a redelivery.
+    if let Err(e) = security.remember(replay_key, now_ms) {
+        log.warn("failed to remember webhook event", &[("error", &e)]);
+    }
+    Ok("")
+}
+
+/// Decodes a delivery body; anything that is not a HeyReach event is a 400.
+fn parse_body(body: &[u8]) -> Result<WebhookEvent, Rejection> {
+    let text = std::str::from_utf8(body)
+        .map_err(|_| Rejection::new(400, "Webhook body is not valid UTF-8"))?;
+
+    client::webhooks_parse_event(text).map_err(|e| Rejection::new(400, &e.message))
+}
+
+fn read_body(request: IncomingRequest) -> Result<Vec<u8>, Rejection> {
+    let incoming_body = request
+        .consume()
+        .map_err(|_| Rejection::new(400, "Webhook body could not be read"))?;
+    let stream = incoming_body
+        .stream()
+        .map_err(|_| Rejection::new(400, "Webhook body could not be read"))?;
+
+    let mut body = Vec::new();
+    loop {
+        match stream.blocking_read(8192) {
+            Ok(chunk) => {
+                body.extend_from_slice(&chunk);
+                if body.len() > MAX_BODY_BYTES {
+                    return Err(Rejection::new(
+                        413,
+                        &format!("Webhook body exceeds {} bytes", MAX_BODY_BYTES),
+                    ));
+                }
+            }
+            Err(StreamError::Closed) => break,
+            Err(_) => return Err(Rejection::new(400, "Webhook body could not be read")),
+        }
+    }
+    drop(stream);
+    IncomingBody::finish(incoming_body);
+
+    Ok(body)
+}
+
+/// Sends `status` with `text` as a plain-text body.
+fn respond(response_out: ResponseOutparam, status: u16, text: &str) {
+    let headers = Fields::new();
+    let _ = headers.append("content-type", b"text/plain; charset=utf-8");
+
+    let response = OutgoingResponse::new(headers);
+    let _ = response.set_status_code(status);
+    let Ok(body) = response.body() else {
+        return;
+    };
+    ResponseOutparam::set(response_out, Ok(response));
+
+    let mut end = text.len().min(MAX_REASON_BYTES);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    if end > 0 {
+        if let Ok(stream) = body.write() {
+            let _ = stream.blocking_write_and_flush(&text.as_bytes()[..end]);
+        }
+    }
+    let _ = OutgoingBody::finish(body, None);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_body_is_parsed() {
+        let event = parse_body(
+            br#"{"event_type":"connection_request_accepted","timestamp":"2024-05-08T10:48:02Z"}"#,
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(event.timestamp, "2024-05-08T10:48:02Z");
+    }
+
+    #[test]
+    fn malformed_bodies_are_bad_requests() {
+        for body in [
+            &b"not json"[..],
+            b"",
+            b"[]",
+            br#"{"timestamp":"2024-05-08T10:48:02Z"}"#,
+            br#"{"event_type":" ","timestamp":"2024-05-08T10:48:02Z"}"#,
+            b"{\"event_type\":\"\xff\"}",
+        ] {
+            let rejection = parse_body(body).err().unwrap();
+            assert_eq!(rejection.status, 400, "{:?}", String::from_utf8_lossy(body));
+            assert!(!rejection.reason.is_empty());
+        }
+    }
+}
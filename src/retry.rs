@@ -0,0 +1,258 @@
+use crate::clock;
+use crate::exports::heyreach::client::api::RetryPolicy;
+
+/// How a call may be retried after a transient failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryMode {
+    /// Read-only call; retried by default.
+    Read,
+    /// Write that is safe to repeat; retried only with `retry-writes`.
+    Write,
+    /// Write with visible side effects; never retried.
+    Never,
+}
+
+impl RetryMode {
+    pub fn max_attempts(self, policy: &RetryPolicy) -> u32 {
+        let allowed = match self {
+            RetryMode::Read => true,
+            RetryMode::Write => policy.retry_writes,
+            RetryMode::Never => false,
+        };
+
+        if allowed {
+            policy.max_attempts.max(1)
+        } else {
+            1
+        }
+    }
+}
+
+pub fn default_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_backoff_ms: 500,
+        max_backoff_ms: 30_000,
+        jitter: true,
+        retry_writes: false,
+    }
+}
+
+/// 429 and the 5xx codes that usually clear up on their own.
+pub fn is_retryable_status(status: u16) -> bool {
+    matches!(status, 429 | 502 | 503 | 504)
+}
+
+/// Exponential backoff before attempt `attempt + 1`, where `attempt` is the
+/// 1-based number of the attempt that just failed.
+pub fn backoff_ms(policy: &RetryPolicy, attempt: u32) -> u64 {
+    let exponent = attempt.saturating_sub(1).min(31);
+    let delay = (policy.initial_backoff_ms as u64)
+        .saturating_mul(1 << exponent)
+        .min(policy.max_backoff_ms as u64);
+
+    if policy.jitter && delay > 1 {
+        // "Equal jitter": keep half of the delay, randomise the other half.
+        let half = delay / 2;
+        half + random_u64() % (delay - half + 1)
+    } else {
+        delay
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn random_u64() -> u64 {
+    crate::wasi::random::random::get_random_u64()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn random_u64() -> u64 {
+    use std::hash::BuildHasher;
+
+    std::collections::hash_map::RandomState::new().hash_one(clock::now_ns())
+}
+
+/// Parses a `Retry-After` header value (delta-seconds or an IMF-fixdate such
+/// as `Wed, 21 Oct 2015 07:28:00 GMT`) into a wait in milliseconds.
+pub fn parse_retry_after_ms(value: &str) -> Option<u64> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(seconds.saturating_mul(1000));
+    }
+
+    let target = parse_http_date(value)?;
+    Some((target * 1000).saturating_sub(clock::unix_now_ms()))
+}
+
+/// Parses an IMF-fixdate into seconds since the Unix epoch.
+fn parse_http_date(value: &str) -> Option<u64> {
+    // "Wed, 21 Oct 2015 07:28:00 GMT"
+    let (_, rest) = value.split_once(", ")?;
+    let mut parts = rest.split(' ');
+    let day: u64 = parts.next()?.parse().ok()?;
+    let month = match parts.next()? {
+        "Jan" => 1,
+        "Feb" => 2,
+        "Mar" => 3,
+        "Apr" => 4,
+        "May" => 5,
+        "Jun" => 6,
+        "Jul" => 7,
+        "Aug" => 8,
+        "Sep" => 9,
+        "Oct" => 10,
+        "Nov" => 11,
+        "Dec" => 12,
+        _ => return None,
+    };
+    let year: u64 = parts.next()?.parse().ok()?;
+    let mut time = parts.next()?.split(':');
+    let hour: u64 = time.next()?.parse().ok()?;
+    let minute: u64 = time.next()?.parse().ok()?;
+    let second: u64 = time.next()?.parse().ok()?;
+
+    if parts.next()? != "GMT"
+        || !(1970..=9999).contains(&year)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    Some(days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's
+/// `days_from_civil`, restricted to dates after the epoch).
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(jitter: bool) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 4_000,
+            jitter,
+            retry_writes: false,
+        }
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let policy = policy(false);
+        let delays: Vec<u64> = (1..=6)
+            .map(|attempt| backoff_ms(&policy, attempt))
+            .collect();
+        assert_eq!(delays, [500, 1_000, 2_000, 4_000, 4_000, 4_000]);
+        assert_eq!(backoff_ms(&policy, u32::MAX), 4_000);
+    }
+
+    #[test]
+    fn jittered_backoff_stays_in_upper_half() {
+        let policy = policy(true);
+        for attempt in 1..=6 {
+            let full = backoff_ms(
+                &RetryPolicy {
+                    jitter: false,
+                    ..policy
+                },
+                attempt,
+            );
+            for _ in 0..50 {
+                let delay = backoff_ms(&policy, attempt);
+                assert!(delay >= full / 2 && delay <= full, "{} of {}", delay, full);
+            }
+        }
+    }
+
+    #[test]
+    fn retry_after_delta_seconds() {
+        assert_eq!(parse_retry_after_ms("0"), Some(0));
+        assert_eq!(parse_retry_after_ms(" 120 "), Some(120_000));
+        assert_eq!(parse_retry_after_ms("18446744073709551615"), Some(u64::MAX));
+    }
+
+    #[test]
+    fn retry_after_http_date() {
+        assert_eq!(
+            parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(1_445_412_480)
+        );
+        assert_eq!(parse_http_date("Thu, 01 Jan 1970 00:00:00 GMT"), Some(0));
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 23:59:59 GMT"),
+            Some(1_709_251_199)
+        );
+        // A date in the past means "retry now".
+        assert_eq!(
+            parse_retry_after_ms("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(0)
+        );
+
+        let in_a_minute = clock::unix_now_ms() / 1000 + 60;
+        let days = in_a_minute / 86_400;
+        let secs = in_a_minute % 86_400;
+        let (year, month, day) = civil_from_days(days);
+        let header = format!(
+            "Mon, {:02} {} {} {:02}:{:02}:{:02} GMT",
+            day,
+            ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"]
+                [month as usize - 1],
+            year,
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        );
+        let wait = parse_retry_after_ms(&header).unwrap();
+        assert!((55_000..=60_000).contains(&wait), "{}", wait);
+    }
+
+    #[test]
+    fn retry_after_rejects_bad_input() {
+        for value in [
+            "",
+            "-5",
+            "1.5",
+            "soon",
+            "Wed, 21 Oct 2015 07:28:00 UTC",
+            "Wed, 21 Foo 2015 07:28:00 GMT",
+            "Wed, 32 Oct 2015 07:28:00 GMT",
+            "Wed, 21 Oct 1969 07:28:00 GMT",
+            "Wed, 21 Oct 2015 24:28:00 GMT",
+            "Wed, 21 Oct 2015 07:28 GMT",
+            "Wed, 21 Oct 99999999999999999 07:28:00 GMT",
+            "Wed, 21 Oct 2015 99999999999999999:00:00 GMT",
+        ] {
+            assert_eq!(parse_retry_after_ms(value), None, "{:?}", value);
+        }
+    }
+
+    /// Inverse of `days_from_civil`, for building headers in tests.
+    fn civil_from_days(days: u64) -> (u64, u64, u64) {
+        let z = days + 719_468;
+        let era = z / 146_097;
+        let day_of_era = z - era * 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + u64::from(month <= 2);
+        (year, month, day)
+    }
+}
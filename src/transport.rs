@@ -0,0 +1,480 @@
+use crate::clock;
+use crate::config::HttpConfig;
+use crate::exports::heyreach::client::api::{ApiError, ApiErrorCode, Timeouts, UrlScheme};
+use crate::http::{api_error, HttpMethod};
+use crate::log::Logger;
+use crate::retry::RetryMode;
+use crate::wasi::http::outgoing_handler;
+use crate::wasi::http::types::*;
+use crate::wasi::io::streams::StreamError;
+#[cfg(test)]
+use std::cell::RefCell;
+#[cfg(test)]
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// A fully built API request, as seen by transports and layers.
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: HttpMethod,
+    /// Path and query relative to the API root, e.g. `/api/public/list/GetAll`.
+    pub path: String,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: Option<Vec<u8>>,
+    pub timeouts: Timeouts,
+    pub retry: RetryMode,
+    /// 1-based attempt number, maintained by the retry layer.
+    pub attempt: u32,
+}
+
+impl Request {
+    /// Path without the query string, used to label logs and errors.
+    pub fn endpoint(&self) -> &str {
+        endpoint(&self.path)
+    }
+}
+
+/// `path` without its query string.
+pub fn endpoint(path: &str) -> &str {
+    path.split('?').next().unwrap_or(path)
+}
+
+#[derive(Debug, Clone)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// First value of the header `name` (case-insensitive), if valid UTF-8.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .and_then(|(_, v)| std::str::from_utf8(v).ok())
+    }
+}
+
+/// A request that produced no response; `transient` failures (network
+/// errors, timeouts) may be retried.
+#[derive(Debug, Clone)]
+pub struct TransportError {
+    pub error: ApiError,
+    pub transient: bool,
+}
+
+impl TransportError {
+    pub fn fatal(code: ApiErrorCode, message: &str) -> Self {
+        TransportError {
+            error: api_error(code, message),
+            transient: false,
+        }
+    }
+
+    pub fn transient(code: ApiErrorCode, message: &str) -> Self {
+        TransportError {
+            error: api_error(code, message),
+            transient: true,
+        }
+    }
+}
+
+/// Sends a request and returns the complete response.
+///
+/// Implemented by the real network transports and by the layers in
+/// `layers.rs`, which wrap another transport.
+pub trait Transport {
+    fn send(&self, request: &mut Request) -> Result<Response, TransportError>;
+}
+
+impl<T: Transport + ?Sized> Transport for Rc<T> {
+    fn send(&self, request: &mut Request) -> Result<Response, TransportError> {
+        (**self).send(request)
+    }
+}
+
+// -------- wasi:http --------
+
+/// Production transport built on `wasi:http/outgoing-handler`.
+pub struct WasiHttpTransport {
+    scheme: UrlScheme,
+    authority: String,
+    path_prefix: String,
+    log: Logger,
+}
+
+impl WasiHttpTransport {
+    pub fn new(config: &HttpConfig) -> Self {
+        WasiHttpTransport {
+            scheme: config.scheme,
+            authority: config.authority.clone(),
+            path_prefix: config.path_prefix.clone(),
+            log: Logger::new(config.log_level),
+        }
+    }
+}
+
+impl Transport for WasiHttpTransport {
+    fn send(&self, request: &mut Request) -> Result<Response, TransportError> {
+        let headers = Fields::new();
+
+        for (name, value) in &request.headers {
+            headers.append(name, value).map_err(|e| {
+                self.log.error(
+                    "failed to append header",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("header", name),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                TransportError::fatal(
+                    ApiErrorCode::Unknown,
+                    &format!("Failed to append {} header", name),
+                )
+            })?;
+        }
+
+        let outgoing_request = OutgoingRequest::new(headers);
+
+        let method_value = match request.method {
+            HttpMethod::Get => Method::Get,
+            HttpMethod::Post => Method::Post,
+            HttpMethod::Patch => Method::Patch,
+            HttpMethod::Delete => Method::Delete,
+        };
+
+        outgoing_request.set_method(&method_value).map_err(|e| {
+            self.log.error(
+                "failed to set method",
+                &[
+                    ("endpoint", &request.endpoint()),
+                    ("error", &format!("{:?}", e)),
+                ],
+            );
+            TransportError::fatal(ApiErrorCode::Unknown, "Failed to set method")
+        })?;
+
+        let full_path = format!("{}{}", self.path_prefix, request.path);
+        outgoing_request
+            .set_path_with_query(Some(&full_path))
+            .map_err(|e| {
+                self.log.error(
+                    "failed to set path",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                TransportError::fatal(ApiErrorCode::Unknown, "Failed to set path")
+            })?;
+
+        outgoing_request
+            .set_scheme(Some(&scheme(self.scheme)))
+            .map_err(|e| {
+                self.log.error(
+                    "failed to set scheme",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                TransportError::fatal(ApiErrorCode::Unknown, "Failed to set scheme")
+            })?;
+
+        outgoing_request
+            .set_authority(Some(&self.authority))
+            .map_err(|e| {
+                self.log.error(
+                    "failed to set authority",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                TransportError::fatal(ApiErrorCode::Unknown, "Failed to set authority")
+            })?;
+
+        if let Some(body_bytes) = &request.body {
+            let outgoing_body = outgoing_request.body().map_err(|e| {
+                self.log.error(
+                    "failed to get outgoing body",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                TransportError::fatal(ApiErrorCode::Unknown, "Failed to get outgoing body")
+            })?;
+
+            let body_stream = outgoing_body.write().map_err(|e| {
+                self.log.error(
+                    "failed to get body stream",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                TransportError::fatal(ApiErrorCode::Unknown, "Failed to get body stream")
+            })?;
+
+            body_stream
+                .blocking_write_and_flush(body_bytes)
+                .map_err(|e| {
+                    self.log.error(
+                        "failed to write body",
+                        &[
+                            ("endpoint", &request.endpoint()),
+                            ("error", &format!("{:?}", e)),
+                        ],
+                    );
+                    TransportError::fatal(ApiErrorCode::Network, "Failed to write body")
+                })?;
+
+            drop(body_stream);
+            OutgoingBody::finish(outgoing_body, None).map_err(|e| {
+                self.log.error(
+                    "failed to finish body",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                TransportError::fatal(ApiErrorCode::Unknown, "Failed to finish body")
+            })?;
+        }
+
+        let connect_ms = request.timeouts.connect_ms.unwrap_or(0) as u64;
+        let first_byte_ms = request.timeouts.first_byte_ms.unwrap_or(0) as u64;
+        let between_bytes_ms = request.timeouts.between_bytes_ms.unwrap_or(0) as u64;
+
+        // Hosts may reject individual options as unsupported; the deadlines
+        // below are enforced regardless.
+        let options = RequestOptions::new();
+        let _ = options.set_connect_timeout(Some(connect_ms * 1_000_000));
+        let _ = options.set_first_byte_timeout(Some(first_byte_ms * 1_000_000));
+        let _ = options.set_between_bytes_timeout(Some(between_bytes_ms * 1_000_000));
+
+        let future_response =
+            outgoing_handler::handle(outgoing_request, Some(options)).map_err(|e| {
+                self.log.warn(
+                    "failed to send request",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                error_from_code(&e, "Failed to send request")
+            })?;
+
+        let response_ready = {
+            let pollable = future_response.subscribe();
+            clock::wait_ready(&pollable, connect_ms + first_byte_ms)
+        };
+        if !response_ready {
+            self.log.warn(
+                "timed out waiting for response",
+                &[("endpoint", &request.endpoint())],
+            );
+            return Err(TransportError::transient(
+                ApiErrorCode::Timeout,
+                &format!("No response within {} ms", connect_ms + first_byte_ms),
+            ));
+        }
+
+        let incoming_response = future_response
+            .get()
+            .ok_or_else(|| {
+                self.log.warn(
+                    "request not completed",
+                    &[("endpoint", &request.endpoint())],
+                );
+                TransportError::transient(ApiErrorCode::Network, "Request not completed")
+            })?
+            .map_err(|e| {
+                self.log.warn(
+                    "request failed",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                TransportError::transient(ApiErrorCode::Network, "Request failed")
+            })?
+            .map_err(|e| {
+                self.log.warn(
+                    "request error",
+                    &[
+                        ("endpoint", &request.endpoint()),
+                        ("error", &format!("{:?}", e)),
+                    ],
+                );
+                error_from_code(&e, "Request error")
+            })?;
+
+        let status = incoming_response.status();
+
+        let response_headers = {
+            let fields = incoming_response.headers();
+            let entries = fields.entries();
+            drop(fields);
+            entries
+        };
+
+        let incoming_body = incoming_response.consume().map_err(|e| {
+            self.log.error(
+                "failed to get response body",
+                &[
+                    ("endpoint", &request.endpoint()),
+                    ("error", &format!("{:?}", e)),
+                ],
+            );
+            TransportError::fatal(ApiErrorCode::Unknown, "Failed to get response body")
+        })?;
+
+        let body_stream = incoming_body.stream().map_err(|e| {
+            self.log.error(
+                "failed to get body stream",
+                &[
+                    ("endpoint", &request.endpoint()),
+                    ("error", &format!("{:?}", e)),
+                ],
+            );
+            TransportError::fatal(ApiErrorCode::Unknown, "Failed to get body stream")
+        })?;
+
+        let mut response_bytes = Vec::new();
+        let readable = body_stream.subscribe();
+        loop {
+            if !clock::wait_ready(&readable, between_bytes_ms) {
+                self.log.warn(
+                    "timed out reading response body",
+                    &[("endpoint", &request.endpoint())],
+                );
+                return Err(TransportError::transient(
+                    ApiErrorCode::Timeout,
+                    &format!("No response data within {} ms", between_bytes_ms),
+                ));
+            }
+
+            match body_stream.read(8192) {
+                // An empty read just means no data yet; keep waiting.
+                Ok(chunk) => response_bytes.extend_from_slice(&chunk),
+                Err(StreamError::Closed) => break,
+                Err(e) => {
+                    self.log.warn(
+                        "failed to read response chunk",
+                        &[
+                            ("endpoint", &request.endpoint()),
+                            ("error", &format!("{:?}", e)),
+                        ],
+                    );
+                    return Err(TransportError::transient(
+                        ApiErrorCode::Network,
+                        "Failed to read response",
+                    ));
+                }
+            }
+        }
+        drop(readable);
+        drop(body_stream);
+
+        Ok(Response {
+            status,
+            headers: response_headers,
+            body: response_bytes,
+        })
+    }
+}
+
+fn scheme(scheme: UrlScheme) -> Scheme {
+    match scheme {
+        UrlScheme::Https => Scheme::Https,
+        UrlScheme::Http => Scheme::Http,
+    }
+}
+
+/// Maps a `wasi:http` error code to `timeout` or `network`.
+fn error_from_code(code: &ErrorCode, context: &str) -> TransportError {
+    let is_timeout = matches!(
+        code,
+        ErrorCode::DnsTimeout
+            | ErrorCode::ConnectionTimeout
+            | ErrorCode::ConnectionReadTimeout
+            | ErrorCode::ConnectionWriteTimeout
+            | ErrorCode::HttpResponseTimeout
+    );
+
+    if is_timeout {
+        TransportError::transient(ApiErrorCode::Timeout, &format!("{}: {:?}", context, code))
+    } else {
+        TransportError::transient(ApiErrorCode::Network, &format!("{}: {:?}", context, code))
+    }
+}
+
+// -------- In-memory --------
+
+/// Transport that answers from a queue of canned responses and records every
+/// request it receives, so `client.rs` can be exercised under native
+/// `cargo test` without a WASI runtime.
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryTransport {
+    responses: RefCell<VecDeque<Result<Response, TransportError>>>,
+    requests: RefCell<Vec<Request>>,
+}
+
+#[cfg(test)]
+impl MemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a response with a JSON body.
+    pub fn push_json(&self, status: u16, body: &str) {
+        self.responses.borrow_mut().push_back(Ok(Response {
+            status,
+            headers: vec![(
+                "content-type".to_string(),
+                b"application/json; charset=utf-8".to_vec(),
+            )],
+            body: body.as_bytes().to_vec(),
+        }));
+    }
+
+    /// Queues a response with the given headers and a plain-text body.
+    pub fn push_status(&self, status: u16, headers: &[(&str, &str)], body: &str) {
+        self.responses.borrow_mut().push_back(Ok(Response {
+            status,
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.as_bytes().to_vec()))
+                .collect(),
+            body: body.as_bytes().to_vec(),
+        }));
+    }
+
+    /// Queues an arbitrary response or transport failure.
+    pub fn push(&self, outcome: Result<Response, TransportError>) {
+        self.responses.borrow_mut().push_back(outcome);
+    }
+
+    /// Every request sent so far, in order.
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.borrow().clone()
+    }
+}
+
+#[cfg(test)]
+impl Transport for MemoryTransport {
+    fn send(&self, request: &mut Request) -> Result<Response, TransportError> {
+        self.requests.borrow_mut().push(request.clone());
+        self.responses.borrow_mut().pop_front().unwrap_or_else(|| {
+            Err(TransportError::fatal(
+                ApiErrorCode::Unknown,
+                &format!("No canned response for {}", request.path),
+            ))
+        })
+    }
+}
@@ -0,0 +1,493 @@
+//! Authenticity and replay checks for inbound webhook deliveries.
+//!
+//! Settings are read from `wasi:config/store` on every request:
+//!
+//! - `webhook-secret` (required): the shared secret.
+//! - `webhook-signature-header` (default `x-heyreach-signature`): header with
+//!   the hex HMAC-SHA256 of the raw body, optionally prefixed by `sha256=`.
+//! - `webhook-token-param` (default `token`): query parameter carrying the
+//!   secret itself, for URLs registered as `https://.../hook?token=<secret>`.
+//! - `webhook-max-age-secs` (default 300): how far an event's `timestamp` may
+//!   be from now, in either direction; 0 disables the check.
+//! - `webhook-dedupe` (default `keyvalue`): where accepted events are
+//!   remembered. `keyvalue` uses a `wasi:keyvalue` bucket shared by every
+//!   instance, `instance` this instance's memory only, `off` nothing.
+//! - `webhook-dedupe-bucket` (default `heyreach-webhook-events`): the bucket
+//!   opened in `keyvalue` mode.
+//!
+//! A delivery is authentic when the signature header or the URL token
+//! matches. Accepted events are remembered by id so redeliveries are
+//! acknowledged without reaching the handler again.
+
+use crate::clock;
+use crate::wasi::config::store;
+use crate::wasi::keyvalue::store as keyvalue;
+use hmac_sha256::{Hash, HMAC};
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+
+const DEFAULT_SIGNATURE_HEADER: &str = "x-heyreach-signature";
+const DEFAULT_TOKEN_PARAM: &str = "token";
+const DEFAULT_MAX_AGE_SECS: u64 = 300;
+const DEFAULT_DEDUPE_BUCKET: &str = "heyreach-webhook-events";
+
+/// Upper bound on remembered event ids; the oldest are dropped first.
+const MAX_REMEMBERED: usize = 10_000;
+
+/// Verification settings for one request.
+#[derive(Debug, Clone)]
+pub struct WebhookSecurity {
+    secret: String,
+    signature_header: String,
+    token_param: String,
+    /// 0 when the timestamp window is disabled.
+    max_age_ms: u64,
+    dedupe: Dedupe,
+}
+
+/// Where accepted events are remembered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Dedupe {
+    /// A `wasi:keyvalue` bucket, shared by every instance that opens it.
+    KeyValue {
+        bucket: String,
+    },
+    /// This instance's memory. Only useful on hosts that keep one instance
+    /// alive across requests; it is lost on restart.
+    Instance,
+    Off,
+}
+
+impl WebhookSecurity {
+    /// Reads the settings from runtime config. Fails when the secret is
+    /// missing, since every delivery would be rejected anyway.
+    pub fn load() -> Result<Self, String> {
+        let secret = config_value("webhook-secret")?
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| "webhook-secret is not configured".to_string())?;
+
+        let max_age_secs = match config_value("webhook-max-age-secs")? {
+            Some(value) => value
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid webhook-max-age-secs '{}'", value))?,
+            None => DEFAULT_MAX_AGE_SECS,
+        };
+
+        let dedupe = match config_value("webhook-dedupe")?.as_deref().map(str::trim) {
+            None | Some("keyvalue") => Dedupe::KeyValue {
+                bucket: config_value("webhook-dedupe-bucket")?
+                    .unwrap_or_else(|| DEFAULT_DEDUPE_BUCKET.to_string()),
+            },
+            Some("instance") => Dedupe::Instance,
+            Some("off") => Dedupe::Off,
+            Some(other) => {
+                return Err(format!(
+                    "Invalid webhook-dedupe '{}', expected 'keyvalue', 'instance' or 'off'",
+                    other
+                ))
+            }
+        };
+
+        Ok(WebhookSecurity {
+            secret,
+            signature_header: config_value("webhook-signature-header")?
+                .unwrap_or_else(|| DEFAULT_SIGNATURE_HEADER.to_string()),
+            token_param: config_value("webhook-token-param")?
+                .unwrap_or_else(|| DEFAULT_TOKEN_PARAM.to_string()),
+            max_age_ms: max_age_secs.saturating_mul(1000),
+            dedupe,
+        })
+    }
+
+    /// Checks the signature header, or else the URL token.
+    pub fn authenticate(
+        &self,
+        headers: &[(String, Vec<u8>)],
+        path_with_query: Option<&str>,
+        body: &[u8],
+    ) -> Result<(), &'static str> {
+        let signature = headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&self.signature_header))
+            .map(|(_, value)| value.as_slice());
+
+        if let Some(signature) = signature {
+            let signature = std::str::from_utf8(signature).map_err(|_| "Invalid signature")?;
+            let signature = signature.trim();
+            let hex = signature.strip_prefix("sha256=").unwrap_or(signature);
+            let expected = decode_hex(hex).ok_or("Invalid signature")?;
+            return if HMAC::verify(body, &self.secret, &expected) {
+                Ok(())
+            } else {
+                Err("Invalid signature")
+            };
+        }
+
+        match query_param(path_with_query.unwrap_or(""), &self.token_param) {
+            Some(token) if constant_time_eq(token.as_bytes(), self.secret.as_bytes()) => Ok(()),
+            Some(_) => Err("Invalid token"),
+            None => Err("Missing signature or token"),
+        }
+    }
+
+    /// Rejects events whose `timestamp` is outside the configured window.
+    pub fn check_timestamp(&self, timestamp: &str, now_ms: u64) -> Result<(), String> {
+        if self.max_age_ms == 0 {
+            return Ok(());
+        }
+
+        let event_ms = clock::parse_timestamp_ms(timestamp)
+            .ok_or_else(|| format!("Invalid event timestamp '{}'", timestamp))?;
+        if now_ms.abs_diff(event_ms) > self.max_age_ms {
+            return Err(format!(
+                "Event timestamp '{}' is outside the accepted window of {} s",
+                timestamp,
+                self.max_age_ms / 1000
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// How long accepted event ids are remembered. Older redeliveries fail
+    /// the timestamp check instead.
+    pub fn replay_retention_ms(&self) -> Option<u64> {
+        (self.max_age_ms > 0).then(|| self.max_age_ms.saturating_mul(2))
+    }
+
+    /// Whether `key` was accepted within the retention period.
+    pub fn already_seen(&self, key: &str, now_ms: u64) -> Result<bool, String> {
+        let retention_ms = self.replay_retention_ms();
+        match &self.dedupe {
+            Dedupe::KeyValue { bucket } => {
+                let seen_at = open_bucket(bucket)?
+                    .get(key)
+                    .map_err(|e| format!("Failed to read {} from {}: {:?}", key, bucket, e))?
+                    .and_then(|value| String::from_utf8(value).ok())
+                    .and_then(|value| value.parse::<u64>().ok());
+                Ok(seen_at.is_some_and(|seen_at| {
+                    retention_ms.is_none_or(|retention| now_ms.saturating_sub(seen_at) <= retention)
+                }))
+            }
+            Dedupe::Instance => {
+                Ok(SEEN.with(|seen| seen.borrow_mut().contains(key, now_ms, retention_ms)))
+            }
+            Dedupe::Off => Ok(false),
+        }
+    }
+
+    /// Records an accepted event. Called only after the handler succeeded,
+    /// so a failed event can still be redelivered.
+    pub fn remember(&self, key: String, now_ms: u64) -> Result<(), String> {
+        match &self.dedupe {
+            Dedupe::KeyValue { bucket } => open_bucket(bucket)?
+                .set(&key, now_ms.to_string().as_bytes())
+                .map_err(|e| format!("Failed to write {} to {}: {:?}", key, bucket, e)),
+            Dedupe::Instance => {
+                SEEN.with(|seen| seen.borrow_mut().insert(key, now_ms));
+                Ok(())
+            }
+            Dedupe::Off => Ok(()),
+        }
+    }
+}
+
+fn open_bucket(bucket: &str) -> Result<keyvalue::Bucket, String> {
+    keyvalue::open(bucket).map_err(|e| format!("Failed to open bucket {}: {:?}", bucket, e))
+}
+
+fn config_value(key: &str) -> Result<Option<String>, String> {
+    store::get(key).map_err(|e| format!("Failed to read {} from config: {:?}", key, e))
+}
+
+/// Key used to recognise a redelivery: the event id, or a hash of the body
+/// for events without one.
+pub fn replay_key(event_id: Option<&str>, body: &[u8]) -> String {
+    match event_id {
+        Some(id) if !id.is_empty() => format!("id:{}", id),
+        _ => format!("body:{}", encode_hex(&Hash::hash(body))),
+    }
+}
+
+/// Event ids accepted by this component instance, oldest first. Nothing here
+/// is shared with other instances or survives a restart.
+#[derive(Default)]
+struct SeenEvents {
+    order: VecDeque<(String, u64)>,
+    keys: HashSet<String>,
+}
+
+impl SeenEvents {
+    /// Whether `key` was accepted within `retention_ms` (forever when `None`).
+    fn contains(&mut self, key: &str, now_ms: u64, retention_ms: Option<u64>) -> bool {
+        if let Some(retention_ms) = retention_ms {
+            while let Some((_, seen_at)) = self.order.front() {
+                if now_ms.saturating_sub(*seen_at) <= retention_ms {
+                    break;
+                }
+                if let Some((old, _)) = self.order.pop_front() {
+                    self.keys.remove(&old);
+                }
+            }
+        }
+        self.keys.contains(key)
+    }
+
+    fn insert(&mut self, key: String, now_ms: u64) {
+        if !self.keys.insert(key.clone()) {
+            return;
+        }
+        self.order.push_back((key, now_ms));
+        while self.order.len() > MAX_REMEMBERED {
+            if let Some((old, _)) = self.order.pop_front() {
+                self.keys.remove(&old);
+            }
+        }
+    }
+}
+
+thread_local! {
+    static SEEN: RefCell<SeenEvents> = RefCell::new(SeenEvents::default());
+}
+
+/// Value of the query parameter `name`, without percent-decoding.
+fn query_param<'a>(path_with_query: &'a str, name: &str) -> Option<&'a str> {
+    let (_, query) = path_with_query.split_once('?')?;
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn decode_hex(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(out)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "s3cret";
+    const BODY: &[u8] = br#"{"eventType":"MESSAGE_REPLY_RECEIVED"}"#;
+    /// `2024-05-08T10:48:02Z` in milliseconds.
+    const EVENT_MS: u64 = 1_715_165_282_000;
+
+    fn security(max_age_secs: u64) -> WebhookSecurity {
+        WebhookSecurity {
+            secret: SECRET.to_string(),
+            signature_header: DEFAULT_SIGNATURE_HEADER.to_string(),
+            token_param: DEFAULT_TOKEN_PARAM.to_string(),
+            max_age_ms: max_age_secs * 1000,
+            dedupe: Dedupe::Instance,
+        }
+    }
+
+    fn signed(body: &[u8]) -> String {
+        encode_hex(&HMAC::mac(body, SECRET))
+    }
+
+    fn signature_header(value: &str) -> Vec<(String, Vec<u8>)> {
+        vec![(
+            "X-HeyReach-Signature".to_string(),
+            value.as_bytes().to_vec(),
+        )]
+    }
+
+    #[test]
+    fn valid_signature_with_or_without_prefix() {
+        let security = security(300);
+        let signature = signed(BODY);
+
+        for value in [signature.clone(), format!("sha256={}", signature)] {
+            assert_eq!(
+                security.authenticate(&signature_header(&value), None, BODY),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn bad_signatures_are_rejected() {
+        let security = security(300);
+        let signature = signed(BODY);
+
+        for value in [
+            signed(b"another body"),
+            signature[..62].to_string(),
+            format!("{}00", signature),
+            "zz".repeat(32),
+            String::new(),
+        ] {
+            assert_eq!(
+                security.authenticate(&signature_header(&value), None, BODY),
+                Err("Invalid signature"),
+                "{:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn wrong_signature_is_not_rescued_by_token() {
+        let security = security(300);
+        let path = format!("/hook?token={}", SECRET);
+
+        assert_eq!(
+            security.authenticate(&signature_header(&signed(b"tampered")), Some(&path), BODY),
+            Err("Invalid signature")
+        );
+    }
+
+    #[test]
+    fn url_token() {
+        let security = security(300);
+
+        assert_eq!(
+            security.authenticate(&[], Some("/hook?a=1&token=s3cret"), BODY),
+            Ok(())
+        );
+        assert_eq!(
+            security.authenticate(&[], Some("/hook?token=s3cres"), BODY),
+            Err("Invalid token")
+        );
+        assert_eq!(
+            security.authenticate(&[], Some("/hook?token="), BODY),
+            Err("Invalid token")
+        );
+        assert_eq!(
+            security.authenticate(&[], Some("/hook?other=s3cret"), BODY),
+            Err("Missing signature or token")
+        );
+        assert_eq!(
+            security.authenticate(&[], None, BODY),
+            Err("Missing signature or token")
+        );
+    }
+
+    #[test]
+    fn timestamp_window_edges() {
+        let security = security(300);
+        let timestamp = "2024-05-08T10:48:02Z";
+
+        assert!(security.check_timestamp(timestamp, EVENT_MS).is_ok());
+        assert!(security
+            .check_timestamp(timestamp, EVENT_MS + 300_000)
+            .is_ok());
+        assert!(security
+            .check_timestamp(timestamp, EVENT_MS - 300_000)
+            .is_ok());
+        assert!(security
+            .check_timestamp(timestamp, EVENT_MS + 300_001)
+            .is_err());
+        assert!(security
+            .check_timestamp(timestamp, EVENT_MS - 300_001)
+            .is_err());
+        assert!(security.check_timestamp("yesterday", EVENT_MS).is_err());
+    }
+
+    #[test]
+    fn zero_max_age_disables_timestamp_check() {
+        let security = security(0);
+
+        assert!(security
+            .check_timestamp("2020-01-01T00:00:00Z", EVENT_MS)
+            .is_ok());
+        assert!(security.check_timestamp("yesterday", EVENT_MS).is_ok());
+        assert_eq!(security.replay_retention_ms(), None);
+    }
+
+    #[test]
+    fn seen_events_expire_after_retention() {
+        let mut seen = SeenEvents::default();
+        seen.insert("id:1".to_string(), 1_000);
+        seen.insert("id:2".to_string(), 5_000);
+
+        assert!(seen.contains("id:1", 2_000, Some(1_000)));
+        assert!(!seen.contains("id:1", 2_001, Some(1_000)));
+        assert!(seen.contains("id:2", 2_001, Some(1_000)));
+        assert_eq!(seen.order.len(), 1);
+
+        seen.insert("id:3".to_string(), 0);
+        assert!(seen.contains("id:3", u64::MAX, None));
+    }
+
+    #[test]
+    fn seen_events_are_capped() {
+        let mut seen = SeenEvents::default();
+        for i in 0..=MAX_REMEMBERED {
+            seen.insert(format!("id:{}", i), 0);
+        }
+        seen.insert("id:1".to_string(), 0);
+
+        assert_eq!(seen.order.len(), MAX_REMEMBERED);
+        assert_eq!(seen.keys.len(), MAX_REMEMBERED);
+        assert!(!seen.contains("id:0", 0, None));
+        assert!(seen.contains("id:1", 0, None));
+        assert!(seen.contains(&format!("id:{}", MAX_REMEMBERED), 0, None));
+    }
+
+    #[test]
+    fn instance_dedupe_remembers_accepted_events() {
+        let security = security(300);
+        let key = replay_key(Some("evt-1"), BODY);
+
+        assert_eq!(security.already_seen(&key, EVENT_MS), Ok(false));
+        security.remember(key.clone(), EVENT_MS).unwrap();
+        assert_eq!(security.already_seen(&key, EVENT_MS + 600_000), Ok(true));
+        assert_eq!(security.already_seen(&key, EVENT_MS + 600_001), Ok(false));
+    }
+
+    #[test]
+    fn dedupe_off_never_sees_events() {
+        let security = WebhookSecurity {
+            dedupe: Dedupe::Off,
+            ..security(300)
+        };
+        security.remember("id:x".to_string(), EVENT_MS).unwrap();
+        assert_eq!(security.already_seen("id:x", EVENT_MS), Ok(false));
+    }
+
+    #[test]
+    fn replay_key_falls_back_to_body_hash() {
+        assert_eq!(replay_key(Some("42"), BODY), "id:42");
+        let by_body = replay_key(Some(""), BODY);
+        assert_eq!(by_body, replay_key(None, BODY));
+        assert_eq!(by_body.len(), "body:".len() + 64);
+        assert_ne!(by_body, replay_key(None, b"{}"));
+    }
+
+    #[test]
+    fn hex_and_comparison_helpers() {
+        let bytes: [u8; 32] = std::array::from_fn(|i| (i * 8) as u8);
+        let hex = encode_hex(&bytes);
+        assert_eq!(decode_hex(&hex), Some(bytes));
+        assert_eq!(decode_hex(&hex.to_uppercase()), Some(bytes));
+        assert_eq!(decode_hex(&hex[..63]), None);
+        assert_eq!(decode_hex(&format!("{}g", &hex[..63])), None);
+
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret!"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}